@@ -37,6 +37,12 @@ except ImportError:
         return evaluate_position_fast(pegs, num_moves)
     
     def rust_evaluate_batch(pegs_list, moves_list):
+        # Как и Rust версия: разная длина списков — ошибка, а не обрезка
+        if len(pegs_list) != len(moves_list):
+            raise ValueError(
+                f"pegs_list and moves_list have different lengths: "
+                f"{len(pegs_list)} and {len(moves_list)}"
+            )
         return [rust_evaluate_position(p, m) for p, m in zip(pegs_list, moves_list)]
 
 
@@ -0,0 +1,41 @@
+/*
+ * rust_peg_solver/benches/solve.rs
+ *
+ * Замер solve_core без PyO3 на стандартном старте (дыра в центре).
+ * Запуск: cargo bench --bench solve
+ */
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rust_peg_solver::{solve_core, VALID_MASK};
+
+// Стандартный старт: вся доска без центральной клетки 24
+const CENTER_START: u64 = VALID_MASK & !(1 << 24);
+
+const WARMUP_RUNS: u32 = 3;
+const MEASURED_RUNS: u32 = 50;
+
+fn main() {
+    assert!(solve_core(CENTER_START).is_some());
+    for _ in 0..WARMUP_RUNS {
+        black_box(solve_core(black_box(CENTER_START)));
+    }
+
+    let mut best = Duration::MAX;
+    let mut total = Duration::ZERO;
+    for _ in 0..MEASURED_RUNS {
+        let started = Instant::now();
+        black_box(solve_core(black_box(CENTER_START)));
+        let elapsed = started.elapsed();
+        best = best.min(elapsed);
+        total += elapsed;
+    }
+
+    println!(
+        "solve_core(center start): best {:?}, mean {:?} over {} runs",
+        best,
+        total / MEASURED_RUNS,
+        MEASURED_RUNS
+    );
+}
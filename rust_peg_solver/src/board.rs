@@ -0,0 +1,393 @@
+/*
+ * rust_peg_solver/src/board.rs
+ *
+ * Описания досок: английская (33 позиции) и европейская (37 позиций).
+ * Обе лежат на сетке 7x7, поэтому используют те же сдвиги 1/7/14,
+ * отличаются только маской валидных клеток и pagoda весами. Маска передаётся
+ * константой в generate_moves_fixed, так что у каждой доски свой код без ветвлений.
+ * CustomBoard задаёт произвольную форму на сетке любой ширины.
+ * Треугольная доска (15 лунок) в сетку не ложится: у неё шесть направлений,
+ * поэтому ходы берутся из заранее построенной таблицы прыжков.
+ * Доска Виглеба (45 лунок) лежит на сетке 9x9, которая в u64 не помещается:
+ * её клетки нумеруются подряд, а прыжки тоже берутся из таблицы.
+ */
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{
+    generate_moves_fixed, generate_moves_grid, is_dead_fixed, is_dead_grid, left_jump_columns,
+    pagoda_on, popcount64, right_jump_columns, Jump, PAGODA_WEIGHTS, VALID_MASK,
+    VALID_POSITIONS,
+};
+
+// Валидные позиции европейской доски (37 позиций, есть диагональные углы 8, 12, 36, 40)
+const EUROPEAN_POSITIONS: [u8; 37] = [
+    2, 3, 4,
+    8, 9, 10, 11, 12,
+    14, 15, 16, 17, 18, 19, 20,
+    21, 22, 23, 24, 25, 26, 27,
+    28, 29, 30, 31, 32, 33, 34,
+    36, 37, 38, 39, 40,
+    44, 45, 46,
+];
+
+const EUROPEAN_MASK: u64 = 0b0011100_0111110_1111111_1111111_1111111_0111110_0011100;
+
+// Pagoda веса европейской доски, параллельно EUROPEAN_POSITIONS
+const EUROPEAN_PAGODA_WEIGHTS: [u32; 37] = [
+    1, 2, 1,  // 2, 3, 4
+    2, 2, 4, 2, 2,  // 8-12
+    1, 2, 3, 4, 3, 2, 1,  // 14-20
+    2, 4, 4, 6, 4, 4, 2,  // 21-27
+    1, 2, 3, 4, 3, 2, 1,  // 28-34
+    2, 2, 4, 2, 2,  // 36-40
+    1, 2, 1,  // 44, 45, 46
+];
+
+// Треугольная доска: 5 рядов, клетка (r, c) при c <= r имеет номер r * (r + 1) / 2 + c
+const TRIANGULAR_ROWS: i32 = 5;
+
+const TRIANGULAR_POSITIONS: [u8; 15] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14];
+
+const TRIANGULAR_MASK: u64 = (1 << 15) - 1;
+
+// Тривиальные pagoda веса: сумма равна числу колышков
+const TRIANGULAR_PAGODA_WEIGHTS: [u32; 15] = [1; 15];
+
+// Все прыжки треугольной доски: 3 оси по 2 направления
+const TRIANGULAR_JUMPS: [Jump; 36] = build_triangular_jumps();
+
+const fn triangular_index(r: i32, c: i32) -> Option<u8> {
+    if r < 0 || r >= TRIANGULAR_ROWS || c < 0 || c > r {
+        return None;
+    }
+    Some((r * (r + 1) / 2 + c) as u8)
+}
+
+const fn build_triangular_jumps() -> [Jump; 36] {
+    const DIRECTIONS: [(i32, i32); 6] = [(0, 1), (0, -1), (1, 0), (-1, 0), (1, 1), (-1, -1)];
+    let mut jumps = [(0u8, 0u8, 0u8); 36];
+    let mut count = 0;
+    let mut r = 0;
+    while r < TRIANGULAR_ROWS {
+        let mut c = 0;
+        while c <= r {
+            let mut d = 0;
+            while d < DIRECTIONS.len() {
+                let (dr, dc) = DIRECTIONS[d];
+                if let (Some(from), Some(over), Some(to)) = (
+                    triangular_index(r, c),
+                    triangular_index(r + dr, c + dc),
+                    triangular_index(r + 2 * dr, c + 2 * dc),
+                ) {
+                    jumps[count] = (from, over, to);
+                    count += 1;
+                }
+                d += 1;
+            }
+            c += 1;
+        }
+        r += 1;
+    }
+    assert!(count == 36);
+    jumps
+}
+
+// Ходы по таблице прыжков: колышки на from и over, дырка на to
+fn table_moves(pegs: u64, jumps: &[Jump]) -> Vec<Jump> {
+    jumps
+        .iter()
+        .copied()
+        .filter(|&(from, over, to)| {
+            (pegs >> from) & 1 != 0 && (pegs >> over) & 1 != 0 && (pegs >> to) & 1 == 0
+        })
+        .collect()
+}
+
+// Доска Виглеба: крест с рукавами шириной 3 на сетке 9x9, клетки 0..45 по рядам
+const WIEGLEB_WIDTH: i32 = 9;
+
+const WIEGLEB_POSITIONS: [u8; 45] = sequential_positions();
+
+const WIEGLEB_MASK: u64 = (1 << 45) - 1;
+
+// Тривиальные pagoda веса: сумма равна числу колышков
+const WIEGLEB_PAGODA_WEIGHTS: [u32; 45] = [1; 45];
+
+const WIEGLEB_JUMPS: [Jump; 108] = build_wiegleb_jumps();
+
+const fn sequential_positions<const N: usize>() -> [u8; N] {
+    let mut positions = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        positions[i] = i as u8;
+        i += 1;
+    }
+    positions
+}
+
+// Номер клетки (r, c) сетки 9x9: 3 ряда по 3, 3 ряда по 9, 3 ряда по 3
+const fn wiegleb_index(r: i32, c: i32) -> Option<u8> {
+    if r < 0 || r >= WIEGLEB_WIDTH || c < 0 || c >= WIEGLEB_WIDTH {
+        return None;
+    }
+    let in_column_arm = c >= 3 && c <= 5;
+    let index = if r < 3 && in_column_arm {
+        r * 3 + (c - 3)
+    } else if r >= 3 && r <= 5 {
+        9 + (r - 3) * WIEGLEB_WIDTH + c
+    } else if r > 5 && in_column_arm {
+        36 + (r - 6) * 3 + (c - 3)
+    } else {
+        return None;
+    };
+    Some(index as u8)
+}
+
+const fn build_wiegleb_jumps() -> [Jump; 108] {
+    const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+    let mut jumps = [(0u8, 0u8, 0u8); 108];
+    let mut count = 0;
+    let mut r = 0;
+    while r < WIEGLEB_WIDTH {
+        let mut c = 0;
+        while c < WIEGLEB_WIDTH {
+            let mut d = 0;
+            while d < DIRECTIONS.len() {
+                let (dr, dc) = DIRECTIONS[d];
+                if let (Some(from), Some(over), Some(to)) = (
+                    wiegleb_index(r, c),
+                    wiegleb_index(r + dr, c + dc),
+                    wiegleb_index(r + 2 * dr, c + 2 * dc),
+                ) {
+                    jumps[count] = (from, over, to);
+                    count += 1;
+                }
+                d += 1;
+            }
+            c += 1;
+        }
+        r += 1;
+    }
+    assert!(count == 108);
+    jumps
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BoardKind {
+    English,
+    European,
+    Triangular,
+    Wiegleb,
+}
+
+/// Доска для игры: выбирает маску, позиции и pagoda веса
+#[pyclass(frozen)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Board {
+    kind: BoardKind,
+}
+
+impl Board {
+    fn mask(&self) -> u64 {
+        match self.kind {
+            BoardKind::English => VALID_MASK,
+            BoardKind::European => EUROPEAN_MASK,
+            BoardKind::Triangular => TRIANGULAR_MASK,
+            BoardKind::Wiegleb => WIEGLEB_MASK,
+        }
+    }
+
+    fn positions(&self) -> &'static [u8] {
+        match self.kind {
+            BoardKind::English => &VALID_POSITIONS,
+            BoardKind::European => &EUROPEAN_POSITIONS,
+            BoardKind::Triangular => &TRIANGULAR_POSITIONS,
+            BoardKind::Wiegleb => &WIEGLEB_POSITIONS,
+        }
+    }
+
+    fn pagoda_weights(&self) -> &'static [u32] {
+        match self.kind {
+            BoardKind::English => &PAGODA_WEIGHTS,
+            BoardKind::European => &EUROPEAN_PAGODA_WEIGHTS,
+            BoardKind::Triangular => &TRIANGULAR_PAGODA_WEIGHTS,
+            BoardKind::Wiegleb => &WIEGLEB_PAGODA_WEIGHTS,
+        }
+    }
+}
+
+#[pymethods]
+impl Board {
+    /// Английская доска (33 позиции)
+    #[staticmethod]
+    fn english() -> Self {
+        Board { kind: BoardKind::English }
+    }
+
+    /// Европейская доска (37 позиций)
+    #[staticmethod]
+    fn european() -> Self {
+        Board { kind: BoardKind::European }
+    }
+
+    /// Треугольная доска (15 позиций, шесть направлений прыжков)
+    #[staticmethod]
+    fn triangular() -> Self {
+        Board { kind: BoardKind::Triangular }
+    }
+
+    /// Доска Виглеба (45 позиций на сетке 9x9, клетки пронумерованы подряд)
+    #[staticmethod]
+    fn wiegleb() -> Self {
+        Board { kind: BoardKind::Wiegleb }
+    }
+
+    #[getter]
+    fn name(&self) -> &'static str {
+        match self.kind {
+            BoardKind::English => "english",
+            BoardKind::European => "european",
+            BoardKind::Triangular => "triangular",
+            BoardKind::Wiegleb => "wiegleb",
+        }
+    }
+
+    #[getter]
+    fn valid_mask(&self) -> u64 {
+        self.mask()
+    }
+
+    #[getter]
+    fn valid_positions(&self) -> Vec<u8> {
+        self.positions().to_vec()
+    }
+
+    /// Подсчёт колышков внутри маски доски
+    fn peg_count(&self, pegs: u64) -> PyResult<u32> {
+        Ok(popcount64(pegs & self.mask()))
+    }
+
+    /// Генерирует все допустимые ходы на этой доске
+    fn get_moves(&self, pegs: u64) -> PyResult<Vec<Jump>> {
+        let pegs = pegs & self.mask();
+        match self.kind {
+            BoardKind::English => Ok(generate_moves_fixed::<7, VALID_MASK>(pegs)),
+            BoardKind::European => Ok(generate_moves_fixed::<7, EUROPEAN_MASK>(pegs)),
+            BoardKind::Triangular => Ok(table_moves(pegs, &TRIANGULAR_JUMPS)),
+            BoardKind::Wiegleb => Ok(table_moves(pegs, &WIEGLEB_JUMPS)),
+        }
+    }
+
+    /// Проверка тупика: нет ходов, но > 1 колышка
+    fn is_dead(&self, pegs: u64) -> PyResult<bool> {
+        let pegs = pegs & self.mask();
+        match self.kind {
+            BoardKind::English => Ok(is_dead_fixed::<7, VALID_MASK>(pegs)),
+            BoardKind::European => Ok(is_dead_fixed::<7, EUROPEAN_MASK>(pegs)),
+            BoardKind::Triangular => {
+                Ok(popcount64(pegs) > 1 && table_moves(pegs, &TRIANGULAR_JUMPS).is_empty())
+            }
+            BoardKind::Wiegleb => {
+                Ok(popcount64(pegs) > 1 && table_moves(pegs, &WIEGLEB_JUMPS).is_empty())
+            }
+        }
+    }
+
+    /// Pagoda функция с весами этой доски
+    fn pagoda_value(&self, pegs: u64) -> PyResult<u32> {
+        Ok(pagoda_on(pegs & self.mask(), self.positions(), self.pagoda_weights()))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Board.{}()", self.name())
+    }
+}
+
+/// Доска произвольной формы на прямоугольной сетке заданной ширины
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub(crate) struct CustomBoard {
+    mask: u64,
+    positions: Vec<u8>,
+    width: u8,
+    right_columns: u64,
+    left_columns: u64,
+}
+
+#[pymethods]
+impl CustomBoard {
+    /// Доска из списка валидных позиций (pos = ряд * width + столбец)
+    #[new]
+    fn new(positions: Vec<u8>, width: u8) -> PyResult<Self> {
+        if !(1..32).contains(&width) {
+            return Err(PyValueError::new_err(format!(
+                "width must be between 1 and 31, got {}",
+                width
+            )));
+        }
+
+        let mut mask = 0u64;
+        for &pos in &positions {
+            if pos >= 64 {
+                return Err(PyValueError::new_err(format!(
+                    "position {} does not fit into 64 bits",
+                    pos
+                )));
+            }
+            mask |= 1u64 << pos;
+        }
+
+        Ok(CustomBoard {
+            mask,
+            positions: (0..64).filter(|&pos| (mask >> pos) & 1 != 0).collect(),
+            width,
+            right_columns: right_jump_columns(width),
+            left_columns: left_jump_columns(width),
+        })
+    }
+
+    #[getter]
+    fn width(&self) -> u8 {
+        self.width
+    }
+
+    #[getter]
+    fn valid_mask(&self) -> u64 {
+        self.mask
+    }
+
+    #[getter]
+    fn valid_positions(&self) -> Vec<u8> {
+        self.positions.clone()
+    }
+
+    /// Подсчёт колышков внутри маски доски
+    fn peg_count(&self, pegs: u64) -> PyResult<u32> {
+        Ok(popcount64(pegs & self.mask))
+    }
+
+    /// Генерирует все допустимые ходы на этой доске
+    fn get_moves(&self, pegs: u64) -> PyResult<Vec<Jump>> {
+        Ok(generate_moves_grid(pegs & self.mask, self.mask, &self.positions, self.width))
+    }
+
+    /// Проверка тупика: нет ходов, но > 1 колышка
+    fn is_dead(&self, pegs: u64) -> PyResult<bool> {
+        Ok(is_dead_grid(
+            pegs & self.mask,
+            self.mask,
+            self.width,
+            self.right_columns,
+            self.left_columns,
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CustomBoard(positions={:?}, width={})",
+            self.positions, self.width
+        )
+    }
+}
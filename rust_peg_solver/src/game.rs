@@ -0,0 +1,86 @@
+/*
+ * rust_peg_solver/src/game.rs
+ *
+ * Состояние партии для интерактивной игры: позиция плюс история ходов.
+ * Тонкая обёртка над свободными функциями модуля.
+ */
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::peg_io::render;
+use crate::{apply_jump, generate_moves, is_solved, popcount64, Jump};
+
+/// Партия: позиция и сделанные ходы
+#[pyclass]
+#[derive(Clone, Debug)]
+pub(crate) struct GameState {
+    pegs: u64,
+    history: Vec<Jump>,
+}
+
+#[pymethods]
+impl GameState {
+    #[new]
+    fn new(pegs: u64) -> Self {
+        GameState { pegs, history: Vec::new() }
+    }
+
+    #[getter]
+    fn pegs(&self) -> u64 {
+        self.pegs
+    }
+
+    #[getter]
+    fn history(&self) -> Vec<Jump> {
+        self.history.clone()
+    }
+
+    /// Допустимые ходы в текущей позиции
+    fn moves(&self) -> Vec<Jump> {
+        generate_moves(self.pegs)
+    }
+
+    /// Делает ход, если он легален, и записывает его в историю
+    fn apply(&mut self, mv: Jump) -> PyResult<()> {
+        if !generate_moves(self.pegs).contains(&mv) {
+            return Err(PyValueError::new_err(format!("illegal move {:?}", mv)));
+        }
+        self.pegs = apply_jump(self.pegs, mv);
+        self.history.push(mv);
+        Ok(())
+    }
+
+    /// Отменяет последний ход и возвращает его
+    fn undo(&mut self) -> PyResult<Jump> {
+        let mv = self
+            .history
+            .pop()
+            .ok_or_else(|| PyValueError::new_err("no moves to undo"))?;
+        self.pegs = apply_jump(self.pegs, mv);
+        Ok(mv)
+    }
+
+    /// Остался один колышек (и, если задано, на target_pos)
+    #[pyo3(signature = (target_pos = None))]
+    fn is_solved(&self, target_pos: Option<u8>) -> bool {
+        is_solved(self.pegs, target_pos)
+    }
+
+    fn peg_count(&self) -> u32 {
+        popcount64(self.pegs)
+    }
+
+    /// ASCII сетка текущей позиции
+    fn render(&self) -> String {
+        render(self.pegs)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GameState(pegs={:#x}, moves={})",
+            self.pegs,
+            self.history.len()
+        )
+    }
+}
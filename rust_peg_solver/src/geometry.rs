@@ -0,0 +1,113 @@
+/*
+ * rust_peg_solver/src/geometry.rs
+ *
+ * Геометрия прямоугольной сетки: ширина, высота и пересчёт
+ * позиции бита в (ряд, столбец) и обратно. pos = ряд * width + столбец.
+ */
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Размеры сетки доски
+#[pyclass(frozen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct BoardGeometry {
+    width: u8,
+    height: u8,
+}
+
+/// Сетка 7x7 английской (и европейской) доски
+pub(crate) const ENGLISH_GEOMETRY: BoardGeometry = BoardGeometry { width: 7, height: 7 };
+
+impl BoardGeometry {
+    pub(crate) const fn width(self) -> u8 {
+        self.width
+    }
+
+    pub(crate) const fn height(self) -> u8 {
+        self.height
+    }
+
+    /// Число клеток сетки
+    pub(crate) const fn cells(self) -> u8 {
+        self.width * self.height
+    }
+
+    pub(crate) const fn to_rc(self, pos: u8) -> (u8, u8) {
+        (pos / self.width, pos % self.width)
+    }
+
+    // Имя парное к to_rc, хотя метод и принимает self
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) const fn from_rc(self, r: u8, c: u8) -> u8 {
+        r * self.width + c
+    }
+
+    /// Лежит ли (ряд, столбец) внутри сетки; координаты со знаком для смещений
+    pub(crate) const fn contains(self, r: i32, c: i32) -> bool {
+        r >= 0 && c >= 0 && r < self.height as i32 && c < self.width as i32
+    }
+
+    /// Центральная клетка (для чётных размеров — ближайшая к центру сверху слева)
+    pub(crate) const fn center(self) -> (u8, u8) {
+        ((self.height - 1) / 2, (self.width - 1) / 2)
+    }
+}
+
+#[pymethods]
+impl BoardGeometry {
+    #[new]
+    fn new(width: u8, height: u8) -> PyResult<Self> {
+        if width == 0 || height == 0 || width as u32 * height as u32 > 64 {
+            return Err(PyValueError::new_err(format!(
+                "grid {}x{} does not fit into 64 bits",
+                width, height
+            )));
+        }
+        Ok(BoardGeometry { width, height })
+    }
+
+    /// Сетка английской доски 7x7
+    #[staticmethod]
+    fn english() -> Self {
+        ENGLISH_GEOMETRY
+    }
+
+    #[getter(width)]
+    fn py_width(&self) -> u8 {
+        self.width
+    }
+
+    #[getter(height)]
+    fn py_height(&self) -> u8 {
+        self.height
+    }
+
+    /// Позиция -> (ряд, столбец)
+    #[pyo3(name = "to_rc")]
+    fn py_to_rc(&self, pos: u8) -> PyResult<(u8, u8)> {
+        if pos >= self.cells() {
+            return Err(PyValueError::new_err(format!(
+                "position {} is outside the {}x{} grid",
+                pos, self.width, self.height
+            )));
+        }
+        Ok(self.to_rc(pos))
+    }
+
+    /// (ряд, столбец) -> позиция
+    #[pyo3(name = "from_rc")]
+    fn py_from_rc(&self, r: u8, c: u8) -> PyResult<u8> {
+        if r >= self.height || c >= self.width {
+            return Err(PyValueError::new_err(format!(
+                "cell ({}, {}) is outside the {}x{} grid",
+                r, c, self.width, self.height
+            )));
+        }
+        Ok(self.from_rc(r, c))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BoardGeometry(width={}, height={})", self.width, self.height)
+    }
+}
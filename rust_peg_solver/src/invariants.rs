@@ -0,0 +1,247 @@
+/*
+ * rust_peg_solver/src/invariants.rs
+ *
+ * Быстрые доказательства нерешаемости без поиска.
+ *
+ * Раскраска клеток по (r + c) mod 3 и (r + 2c) mod 3: три клетки любого
+ * прыжка получают три разных цвета, поэтому каждый ход меняет число колышков
+ * каждого цвета ровно на единицу. Чётности попарных сумм этих чисел
+ * сохраняются — это «правило трёх». Pagoda функции дополняют его: сумма
+ * весов не растёт, так что клетка, чей вес больше текущей суммы, недостижима.
+ * Банк pagoda функций применяется вместе со всеми 8 симметриями доски:
+ * образ pagoda функции при симметрии — тоже pagoda функция.
+ *
+ * Разделение на группы: множество клеток, которые вообще могут оказаться
+ * заняты, замыкается по прыжкам; три клетки любого прыжка лежат в одной
+ * компоненте этого множества, так что колышки разных компонент никогда
+ * не встретятся, и в каждой компоненте останется хотя бы один колышек.
+ */
+
+use pyo3::prelude::*;
+
+use crate::symmetry::map_position;
+use crate::{
+    check_target, is_dead, pagoda_value, popcount64, target_unreachable, MOVE_TABLE,
+    PAGODA_TARGET, VALID_MASK, VALID_POSITIONS,
+};
+
+// Центр английской доски — единственная цель встроенных pagoda весов
+const CENTER_POS: u8 = 24;
+
+// Банк pagoda функций английской доски на сетке 7x7 (0 вне доски)
+pub(crate) const PAGODA_BANK: [[[i32; 7]; 7]; 5] = [
+    // Встроенные веса PAGODA_WEIGHTS (цель — центр)
+    [
+        [0, 0, 1, 2, 1, 0, 0],
+        [0, 0, 2, 4, 2, 0, 0],
+        [1, 2, 3, 4, 3, 2, 1],
+        [2, 4, 4, 6, 4, 4, 2],
+        [1, 2, 3, 4, 3, 2, 1],
+        [0, 0, 2, 4, 2, 0, 0],
+        [0, 0, 1, 2, 1, 0, 0],
+    ],
+    // Классическая функция с отрицательными углами рукавов
+    [
+        [0, 0, -1, 1, -1, 0, 0],
+        [0, 0, 1, 1, 1, 0, 0],
+        [-1, 1, 0, 1, 0, 1, -1],
+        [1, 1, 1, 2, 1, 1, 1],
+        [-1, 1, 0, 1, 0, 1, -1],
+        [0, 0, 1, 1, 1, 0, 0],
+        [0, 0, -1, 1, -1, 0, 0],
+    ],
+    // Фибоначчи по расстоянию до центрального столбца
+    [
+        [0, 0, 3, 5, 3, 0, 0],
+        [0, 0, 3, 5, 3, 0, 0],
+        [1, 2, 3, 5, 3, 2, 1],
+        [1, 2, 3, 5, 3, 2, 1],
+        [1, 2, 3, 5, 3, 2, 1],
+        [0, 0, 3, 5, 3, 0, 0],
+        [0, 0, 3, 5, 3, 0, 0],
+    ],
+    // Период 3 по столбцам: из трёх подряд клеток ровно одна нулевая
+    [
+        [0, 0, 1, 0, 1, 0, 0],
+        [0, 0, 1, 0, 1, 0, 0],
+        [0, 1, 1, 0, 1, 1, 0],
+        [0, 1, 1, 0, 1, 1, 0],
+        [0, 1, 1, 0, 1, 1, 0],
+        [0, 0, 1, 0, 1, 0, 0],
+        [0, 0, 1, 0, 1, 0, 0],
+    ],
+    [
+        [0, 0, 0, 1, 1, 0, 0],
+        [0, 0, 0, 1, 1, 0, 0],
+        [1, 1, 0, 1, 1, 0, 1],
+        [1, 1, 0, 1, 1, 0, 1],
+        [1, 1, 0, 1, 1, 0, 1],
+        [0, 0, 0, 1, 1, 0, 0],
+        [0, 0, 0, 1, 1, 0, 0],
+    ],
+];
+
+pub(crate) fn bank_weight(grid: &[[i32; 7]; 7], pos: u8) -> i32 {
+    grid[(pos / 7) as usize][(pos % 7) as usize]
+}
+
+/// Хотя бы одна функция банка (в одной из 8 симметрий) отсекает target_pos
+pub(crate) fn pagoda_screen(pegs: u64, target_pos: u8) -> bool {
+    PAGODA_BANK.iter().any(|grid| {
+        (0..8).any(|sym| {
+            let weight = |pos: u8| bank_weight(grid, map_position(pos, sym));
+            let total: i32 = VALID_POSITIONS
+                .iter()
+                .filter(|&&pos| (pegs >> pos) & 1 != 0)
+                .map(|&pos| weight(pos))
+                .sum();
+            total < weight(target_pos)
+        })
+    })
+}
+
+/// true — банк pagoda функций доказывает, что target_pos недостижима
+#[pyfunction]
+pub(crate) fn rust_pagoda_screen(pegs: u64, target_pos: u8) -> PyResult<bool> {
+    check_target(target_pos)?;
+    Ok(pagoda_screen(pegs, target_pos))
+}
+
+// Цвет клетки сетки 7x7 в раскраске по диагоналям: (r + c) mod 3
+const DIAGONAL_COLORS: [u8; 49] = build_colors(1);
+
+// Цвет клетки в раскраске по антидиагоналям: (r + 2c) mod 3
+const ANTI_DIAGONAL_COLORS: [u8; 49] = build_colors(2);
+
+const fn build_colors(column_factor: usize) -> [u8; 49] {
+    let mut colors = [0u8; 49];
+    let mut pos = 0;
+    while pos < 49 {
+        let (r, c) = (pos / 7, pos % 7);
+        colors[pos] = ((r + column_factor * c) % 3) as u8;
+        pos += 1;
+    }
+    colors
+}
+
+// Число колышков каждого цвета в раскраске colors
+fn color_counts(pegs: u64, colors: &[u8; 49]) -> [u32; 3] {
+    let mut counts = [0u32; 3];
+    for &pos in &VALID_POSITIONS {
+        if (pegs >> pos) & 1 != 0 {
+            counts[colors[pos as usize] as usize] += 1;
+        }
+    }
+    counts
+}
+
+/// Колышки по цветам раскраски (r + c) mod 3
+///
+/// Прыжок меняет каждое из трёх чисел ровно на единицу, поэтому чётности
+/// попарных сумм сохраняются.
+#[pyfunction]
+pub(crate) fn rust_color_invariant(pegs: u64) -> PyResult<(u32, u32, u32)> {
+    let [first, second, third] = color_counts(pegs, &DIAGONAL_COLORS);
+    Ok((first, second, third))
+}
+
+// Класс позиции: по два бита чётности для каждой из двух раскрасок
+pub(crate) fn position_class(pegs: u64) -> u8 {
+    let diagonal = color_counts(pegs, &DIAGONAL_COLORS);
+    let anti_diagonal = color_counts(pegs, &ANTI_DIAGONAL_COLORS);
+
+    let parity = |counts: [u32; 3]| {
+        (((counts[0] + counts[1]) & 1) << 1 | ((counts[1] + counts[2]) & 1)) as u8
+    };
+    parity(diagonal) << 2 | parity(anti_diagonal)
+}
+
+/// Доказуемо нерешаема: ни одна клетка не может остаться последней
+///
+/// Клетка отбрасывается, если её класс раскраски не совпадает с классом
+/// позиции или pagoda функция доказывает, что она недостижима.
+pub(crate) fn provably_unsolvable(pegs: u64) -> bool {
+    match popcount64(pegs) {
+        0 => return true,
+        1 => return false,
+        _ => {}
+    }
+    if is_dead(pegs) {
+        return true;
+    }
+
+    let class = position_class(pegs);
+    VALID_POSITIONS.iter().all(|&target| {
+        position_class(1u64 << target) != class
+            || target_unreachable(pegs, target)
+            || (target == CENTER_POS && pagoda_value(pegs) < PAGODA_TARGET)
+    })
+}
+
+/// true — позиция доказуемо нерешаема, false — возможно решаема
+#[pyfunction]
+pub(crate) fn rust_provably_unsolvable(pegs: u64) -> PyResult<bool> {
+    Ok(provably_unsolvable(pegs))
+}
+
+// Биты трёх клеток прыжка
+fn jump_bits(from_pos: u8, jumped: u8, to_pos: u8) -> u64 {
+    (1u64 << from_pos) | (1u64 << jumped) | (1u64 << to_pos)
+}
+
+/// Колышки делятся на группы, которые не могут повлиять друг на друга
+///
+/// Клетка может стать занятой, только если клетки «откуда» и «через» прыжка
+/// могут быть заняты; прыжки с такими клетками связывают клетки в компоненты.
+/// Две и больше компоненты с колышками — один колышек не останется.
+pub(crate) fn split_unsolvable(pegs: u64) -> bool {
+    let pegs = pegs & VALID_MASK;
+    let enabled = |cells: u64, from: u8, jumped: u8| (cells >> from) & 1 != 0 && (cells >> jumped) & 1 != 0;
+
+    // Надмножество клеток, которые когда-либо могут быть заняты
+    let mut reachable = pegs;
+    loop {
+        let grown = MOVE_TABLE
+            .iter()
+            .filter(|&&(from, jumped, _)| enabled(reachable, from, jumped))
+            .fold(reachable, |acc, &(_, _, to)| acc | 1u64 << to);
+        if grown == reachable {
+            break;
+        }
+        reachable = grown;
+    }
+
+    let active: Vec<u64> = MOVE_TABLE
+        .iter()
+        .filter(|&&(from, jumped, _)| enabled(reachable, from, jumped))
+        .map(|&(from, jumped, to)| jump_bits(from, jumped, to))
+        .collect();
+
+    let mut groups = 0;
+    let mut rest = pegs;
+    while rest != 0 {
+        let mut component = rest & rest.wrapping_neg();
+        loop {
+            let grown = active
+                .iter()
+                .filter(|&&bits| bits & component != 0)
+                .fold(component, |acc, &bits| acc | bits);
+            if grown == component {
+                break;
+            }
+            component = grown;
+        }
+        rest &= !component;
+        groups += 1;
+        if groups > 1 {
+            return true;
+        }
+    }
+    false
+}
+
+/// true — колышки разбиты на независимые группы, решения нет
+#[pyfunction]
+pub(crate) fn rust_split_unsolvable(pegs: u64) -> PyResult<bool> {
+    Ok(split_unsolvable(pegs))
+}
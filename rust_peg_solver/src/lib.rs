@@ -7,7 +7,33 @@
  * Использование SIMD инструкций и оптимизаций компилятора Rust.
  */
 
+// Макросы PyO3 0.22 дают ложные срабатывания этого lint на PyResult
+#![allow(clippy::useless_conversion)]
+
+use std::borrow::Cow;
+use std::slice;
+
+use pyo3::buffer::{Element, PyBuffer};
+use pyo3::exceptions::{PyIndexError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use rayon::prelude::*;
+
+use crate::geometry::ENGLISH_GEOMETRY;
+
+mod board;
+mod game;
+mod geometry;
+mod invariants;
+mod move_iter;
+mod moves;
+mod peg_io;
+mod playout;
+mod solver;
+mod symmetry;
+mod zobrist;
+
+pub use solver::solve_core;
 
 // Валидные позиции английской доски (33 позиции)
 const VALID_POSITIONS: [u8; 33] = [
@@ -18,11 +44,43 @@ const VALID_POSITIONS: [u8; 33] = [
     37, 38, 39, 44, 45, 46,
 ];
 
-const VALID_MASK: u64 = 0b0000000_0000000_1111111_1111111_1111111_1111111_1111111_0000111_0000111_0000000_0000111;
+/// Маска английской доски: 33 клетки креста — ровно биты VALID_POSITIONS
+pub const VALID_MASK: u64 = 0b0011100_0011100_1111111_1111111_1111111_0011100_0011100;
+
+// Обратная таблица к VALID_POSITIONS: индекс клетки по биту, NO_INDEX вне доски
+const NO_INDEX: u8 = u8::MAX;
+const POS_TO_INDEX: [u8; 64] = build_pos_to_index();
+
+const fn build_pos_to_index() -> [u8; 64] {
+    let mut table = [NO_INDEX; 64];
+    let mut i = 0;
+    while i < VALID_POSITIONS.len() {
+        table[VALID_POSITIONS[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+// Pagoda веса, индексированы параллельно VALID_POSITIONS
+const PAGODA_WEIGHTS: [u32; 33] = [
+    1, 2, 1,  // 2, 3, 4
+    2, 4, 2,  // 9, 10, 11
+    1, 2, 3, 4, 3, 2, 1,  // 14-20
+    2, 4, 4, 6, 4, 4, 2,  // 21-27
+    1, 2, 3, 4, 3, 2, 1,  // 28-34
+    2, 4, 2,  // 37, 38, 39
+    1, 2, 1,  // 44, 45, 46
+];
+
+// Pagoda значение одного колышка в центре (CENTER_POS = 24)
+pub(crate) const PAGODA_TARGET: u32 = 6;
+
+/// Ход: (откуда, через какую позицию, куда)
+pub(crate) type Jump = (u8, u8, u8);
 
 // Быстрый popcount используя встроенную функцию CPU
 #[inline(always)]
-fn popcount64(x: u64) -> u32 {
+pub(crate) fn popcount64(x: u64) -> u32 {
     x.count_ones()
 }
 
@@ -32,33 +90,410 @@ fn rust_peg_count(pegs: u64) -> PyResult<u32> {
     Ok(popcount64(pegs))
 }
 
+/// Нижняя граница числа ходов: каждый прыжок снимает ровно один колышек
+#[pyfunction]
+fn rust_min_moves_remaining(pegs: u64) -> PyResult<u32> {
+    Ok(popcount64(pegs).saturating_sub(1))
+}
+
+/// Batch подсчёт колышков (плотный цикл, компилятор векторизует popcount)
+#[pyfunction]
+fn rust_peg_count_batch(pegs_list: Vec<u64>) -> PyResult<Vec<u32>> {
+    Ok(pegs_list.iter().map(|&pegs| popcount64(pegs)).collect())
+}
+
+/// Пустые клетки доски
+#[inline(always)]
+pub(crate) fn holes(pegs: u64) -> u64 {
+    VALID_MASK & !pegs
+}
+
+/// Пустые клетки доски: VALID_MASK & !pegs
+#[pyfunction]
+fn rust_holes(pegs: u64) -> PyResult<u64> {
+    Ok(holes(pegs))
+}
+
+/// Дополнение позиции (синоним rust_holes): колышки там, где были дырки
+#[pyfunction]
+fn rust_complement(pegs: u64) -> PyResult<u64> {
+    Ok(holes(pegs))
+}
+
+/// Стандартная стартовая позиция: полная доска без одной клетки
+///
+/// "center" — d4, "corner" — c1 (угол верхнего рукава), "off-center" — d3.
+#[pyfunction]
+fn rust_standard_start(variant: &str) -> PyResult<u64> {
+    let (row, col) = match variant {
+        "center" => ENGLISH_GEOMETRY.center(),
+        "corner" => (0, 2),
+        "off-center" => (2, 3),
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "unknown start variant {:?} (expected \"center\", \"corner\" or \"off-center\")",
+                other
+            )));
+        }
+    };
+    Ok(VALID_MASK & !(1u64 << ENGLISH_GEOMETRY.from_rc(row, col)))
+}
+
+/// Индекс клетки в VALID_POSITIONS (для массивов, параллельных ему)
+pub(crate) fn position_index(pos: u8) -> Option<usize> {
+    POS_TO_INDEX
+        .get(pos as usize)
+        .filter(|&&index| index != NO_INDEX)
+        .map(|&index| index as usize)
+}
+
+/// Бит позиции -> индекс 0..33 в VALID_POSITIONS
+#[pyfunction]
+fn rust_pos_to_index(pos: u8) -> PyResult<usize> {
+    position_index(pos)
+        .ok_or_else(|| PyValueError::new_err(format!("position {} is not on the board", pos)))
+}
+
+/// Индекс 0..33 в VALID_POSITIONS -> бит позиции
+#[pyfunction]
+fn rust_index_to_pos(index: usize) -> PyResult<u8> {
+    VALID_POSITIONS.get(index).copied().ok_or_else(|| {
+        PyIndexError::new_err(format!(
+            "index {} out of range for {} positions",
+            index,
+            VALID_POSITIONS.len()
+        ))
+    })
+}
+
 /// Проверка наличия колышка на позиции
 #[pyfunction]
 fn rust_has_peg(pegs: u64, pos: u8) -> PyResult<bool> {
+    check_position(pos)?;
     Ok((pegs >> pos) & 1 == 1)
 }
 
+/// Решено: остался ровно один колышек (и, если задано, на target_pos)
+pub(crate) fn is_solved(pegs: u64, target_pos: Option<u8>) -> bool {
+    if popcount64(pegs) != 1 {
+        return false;
+    }
+    match target_pos {
+        Some(pos) => pos < 64 && pegs == 1u64 << pos,
+        None => true,
+    }
+}
+
+/// Проверка решения; target_pos = 24 — финиш в центре английской доски
+#[pyfunction]
+#[pyo3(signature = (pegs, target_pos = None))]
+fn rust_is_solved(pegs: u64, target_pos: Option<u8>) -> PyResult<bool> {
+    Ok(is_solved(pegs, target_pos))
+}
+
+/// Доля пройденного пути: (start - current) / (start - 1), в пределах [0, 1]
+///
+/// Цель — один колышек, поэтому знаменатель start - 1; при start <= 1 путь уже пройден.
+#[pyfunction]
+fn rust_progress(start: u64, current: u64) -> PyResult<f64> {
+    let (start_count, current_count) = (popcount64(start), popcount64(current));
+    if start_count <= 1 {
+        return Ok(1.0);
+    }
+    let removed = start_count as f64 - current_count as f64;
+    Ok((removed / (start_count - 1) as f64).clamp(0.0, 1.0))
+}
+
+/// Применение хода без обёртки PyResult (для внутреннего поиска)
+#[inline(always)]
+pub(crate) fn apply_jump(pegs: u64, (from_pos, jumped, to_pos): Jump) -> u64 {
+    pegs ^ (1u64 << from_pos) ^ (1u64 << jumped) ^ (1u64 << to_pos)
+}
+
 /// Применяет ход — 3 XOR операции
 #[pyfunction]
 fn rust_apply_move(pegs: u64, from_pos: u8, jumped: u8, to_pos: u8) -> PyResult<u64> {
-    Ok(pegs ^ (1u64 << from_pos) ^ (1u64 << jumped) ^ (1u64 << to_pos))
+    for pos in [from_pos, jumped, to_pos] {
+        check_position(pos)?;
+    }
+    Ok(apply_jump(pegs, (from_pos, jumped, to_pos)))
+}
+
+/// Применяет index-й ход из rust_get_moves
+#[pyfunction]
+fn rust_apply_move_index(pegs: u64, index: usize) -> PyResult<u64> {
+    let moves = generate_moves(pegs);
+    let mv = moves.get(index).copied().ok_or_else(|| {
+        PyIndexError::new_err(format!(
+            "move index {} out of range for {} moves",
+            index,
+            moves.len()
+        ))
+    })?;
+    Ok(apply_jump(pegs, mv))
+}
+
+/// Ход по двум клеткам: сам находит съеденный колышек и проверяет легальность
+///
+/// Возвращает новую позицию и позицию снятого колышка (для анимации).
+#[pyfunction]
+fn rust_step(pegs: u64, from_pos: u8, to_pos: u8) -> PyResult<(u64, u8)> {
+    let jumped = ((from_pos as u16 + to_pos as u16) / 2) as u8;
+    let mv = (from_pos, jumped, to_pos);
+    if !generate_moves(pegs).contains(&mv) {
+        return Err(PyValueError::new_err(format!(
+            "illegal move {} -> {}",
+            from_pos, to_pos
+        )));
+    }
+    Ok((apply_jump(pegs, mv), jumped))
+}
+
+/// Отмена хода: тот же XOR, но сначала проверяем, что ход действительно был сделан
+#[pyfunction]
+fn rust_undo_move(pegs: u64, from_pos: u8, jumped: u8, to_pos: u8) -> PyResult<u64> {
+    for pos in [from_pos, jumped, to_pos] {
+        if pos >= 64 {
+            return Err(PyValueError::new_err(format!("position {} is out of range", pos)));
+        }
+    }
+    if (pegs >> to_pos) & 1 == 0 {
+        return Err(PyValueError::new_err(format!(
+            "cannot undo: no peg at destination {}",
+            to_pos
+        )));
+    }
+    for pos in [from_pos, jumped] {
+        if (pegs >> pos) & 1 != 0 {
+            return Err(PyValueError::new_err(format!(
+                "cannot undo: position {} is occupied",
+                pos
+            )));
+        }
+    }
+    Ok(apply_jump(pegs, (from_pos, jumped, to_pos)))
+}
+
+/// Восстанавливает ход по двум позициям, отличающимся ровно одним прыжком
+#[pyfunction]
+fn rust_diff(before: u64, after: u64) -> PyResult<Jump> {
+    let changed = before ^ after;
+    MOVE_TABLE
+        .iter()
+        .copied()
+        .find(|&(from, jumped, to)| {
+            let removed = (1u64 << from) | (1u64 << jumped);
+            changed == removed | (1u64 << to)
+                && before & removed == removed
+                && after & removed == 0
+        })
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "boards {:#x} and {:#x} do not differ by a single jump",
+                before, after
+            ))
+        })
+}
+
+/// Проигрывает последовательность ходов, проверяя каждый; возвращает финальную позицию
+#[pyfunction]
+fn rust_validate_sequence(start: u64, moves: Vec<Jump>) -> PyResult<u64> {
+    let mut pegs = start;
+    for (index, &mv) in moves.iter().enumerate() {
+        if !generate_moves(pegs).contains(&mv) {
+            return Err(PyValueError::new_err(format!(
+                "move {} {:?} is not legal in position {:#x}",
+                index, mv, pegs
+            )));
+        }
+        pegs = apply_jump(pegs, mv);
+    }
+    Ok(pegs)
+}
+
+/// Проверка, что колышки стоят только на валидных позициях
+#[inline(always)]
+pub(crate) fn is_valid_board(pegs: u64) -> bool {
+    pegs & !VALID_MASK == 0
+}
+
+/// Позиция — индекс клетки доски (меньше 64 и внутри VALID_MASK)
+#[inline(always)]
+pub(crate) fn is_valid_position(pos: u8) -> bool {
+    pos < 64 && (VALID_MASK >> pos) & 1 != 0
+}
+
+/// ValueError, если позиция не является клеткой доски
+fn check_position(pos: u8) -> PyResult<()> {
+    if !is_valid_position(pos) {
+        return Err(PyValueError::new_err(format!(
+            "position {} is not on the board",
+            pos
+        )));
+    }
+    Ok(())
+}
+
+#[pyfunction]
+fn rust_is_valid_board(pegs: u64) -> PyResult<bool> {
+    Ok(is_valid_board(pegs))
 }
 
 /// Генерирует все допустимые ходы (оптимизированная версия)
+///
+/// Для пустой доски и доски с одним колышком возвращает пустой список.
+/// В strict режиме бросает ValueError, если заняты биты вне доски.
 #[pyfunction]
-fn rust_get_moves(pegs: u64) -> PyResult<Vec<(u8, u8, u8)>> {
+#[pyo3(signature = (pegs, strict = false))]
+fn rust_get_moves(pegs: u64, strict: bool) -> PyResult<Vec<Jump>> {
+    if strict && !is_valid_board(pegs) {
+        return Err(PyValueError::new_err(format!(
+            "board has pegs outside the valid mask: {:#x}",
+            pegs & !VALID_MASK
+        )));
+    }
+    Ok(generate_moves(pegs))
+}
+
+pub(crate) fn generate_moves(pegs: u64) -> Vec<Jump> {
+    generate_moves_fixed::<7, VALID_MASK>(pegs)
+}
+
+/// Генерация ходов с шириной сетки и маской доски, известными при компиляции
+///
+/// Те же маски, что в generate_moves_grid, но сдвиги WIDTH и 2 * WIDTH
+/// и маски столбцов — константы, и каждая доска получает свой код.
+/// Клетки обходятся по возрастанию, как в VALID_POSITIONS.
+pub(crate) fn generate_moves_fixed<const WIDTH: u8, const MASK: u64>(pegs: u64) -> Vec<Jump> {
     let mut moves = Vec::new();
-    let holes = VALID_MASK & !pegs;
+    let holes = MASK & !pegs;
+    let row = WIDTH as u32;
+    
+    let can_right = pegs & (pegs >> 1) & (holes >> 2) & const { right_jump_columns(WIDTH) };
+    let can_left = pegs & (pegs << 1) & (holes << 2) & const { left_jump_columns(WIDTH) };
+    
+    // Цель берётся из holes, поэтому всегда внутри маски
+    let can_down = pegs & (pegs >> row) & (holes >> (2 * row));
+    let can_up = pegs & (pegs << row) & (holes << (2 * row));
+    
+    let mut rest = MASK;
+    while rest != 0 {
+        let pos = rest.trailing_zeros() as u8;
+        rest &= rest - 1;
+        
+        if (can_right >> pos) & 1 != 0 {
+            moves.push((pos, pos + 1, pos + 2));
+        }
+        if (can_left >> pos) & 1 != 0 {
+            moves.push((pos, pos - 1, pos - 2));
+        }
+        if (can_down >> pos) & 1 != 0 {
+            moves.push((pos, pos + WIDTH, pos + 2 * WIDTH));
+        }
+        if (can_up >> pos) & 1 != 0 {
+            moves.push((pos, pos - WIDTH, pos - 2 * WIDTH));
+        }
+    }
+    
+    moves
+}
+
+/// Ходы, не задействующие заблокированные клетки (стены из битов blocked)
+#[pyfunction]
+fn rust_get_moves_masked(pegs: u64, blocked: u64) -> PyResult<Vec<Jump>> {
+    let mask = VALID_MASK & !blocked;
+    Ok(generate_moves_on(pegs & mask, mask, &VALID_POSITIONS))
+}
+
+/// Ходы, приземляющиеся ближе к центру, чем стартовая клетка
+#[pyfunction]
+fn rust_center_biased_moves(pegs: u64) -> PyResult<Vec<Jump>> {
+    Ok(generate_moves(pegs)
+        .into_iter()
+        .filter(|&(from_pos, _, to_pos)| center_distance(to_pos) < center_distance(from_pos))
+        .collect())
+}
+
+/// Генерация ходов для произвольной маски на сетке шириной 7
+pub(crate) fn generate_moves_on(pegs: u64, mask: u64, positions: &[u8]) -> Vec<Jump> {
+    generate_moves_grid(pegs, mask, positions, 7)
+}
+
+/// Генерация ходов на сетке произвольной ширины: сдвиги 1, width и 2 * width
+///
+/// Ширина должна быть меньше 32, чтобы сдвиг на 2 * width оставался внутри u64.
+pub(crate) fn generate_moves_grid(pegs: u64, mask: u64, positions: &[u8], width: u8) -> Vec<Jump> {
+    let mut moves = Vec::new();
+    let holes = mask & !pegs;
+    let row = width as u32;
     
     // Горизонтальные ходы
     let can_right = pegs & (pegs >> 1) & (holes >> 2);
     let can_left = pegs & (pegs << 1) & (holes << 2);
     
     // Вертикальные ходы
-    let can_down = pegs & (pegs >> 7) & (holes >> 14);
-    let can_up = pegs & (pegs << 7) & (holes << 14);
+    let can_down = pegs & (pegs >> row) & (holes >> (2 * row));
+    let can_up = pegs & (pegs << row) & (holes << (2 * row));
     
-    for &pos in &VALID_POSITIONS {
+    for &pos in positions {
+        // Вправо
+        if (can_right >> pos) & 1 != 0 && pos % width + 2 < width {
+            moves.push((pos, pos + 1, pos + 2));
+        }
+        
+        // Влево
+        if (can_left >> pos) & 1 != 0 && pos % width >= 2 {
+            moves.push((pos, pos - 1, pos - 2));
+        }
+        
+        // Вниз
+        if (can_down >> pos) & 1 != 0 {
+            let to_pos = pos + 2 * width;
+            if to_pos < 64 && (mask >> to_pos) & 1 != 0 {
+                moves.push((pos, pos + width, to_pos));
+            }
+        }
+        
+        // Вверх
+        if (can_up >> pos) & 1 != 0 && pos >= 2 * width {
+            let to_pos = pos - 2 * width;
+            if (mask >> to_pos) & 1 != 0 {
+                moves.push((pos, pos - width, to_pos));
+            }
+        }
+    }
+    
+    moves
+}
+
+/// Обратные ходы: прыжки, которые могли привести в эту позицию
+#[pyfunction]
+fn rust_get_reverse_moves(pegs: u64) -> PyResult<Vec<Jump>> {
+    Ok(generate_reverse_moves(pegs))
+}
+
+pub(crate) fn generate_reverse_moves(pegs: u64) -> Vec<Jump> {
+    generate_reverse_moves_on(pegs, VALID_MASK, &VALID_POSITIONS)
+}
+
+/// Обратная генерация: from и jumped пусты, на to стоит колышек
+///
+/// Те же маски, что в generate_moves_on, но колышки и дырки меняются ролями.
+/// Применение хода через apply_jump даёт позицию-предшественника.
+pub(crate) fn generate_reverse_moves_on(pegs: u64, mask: u64, positions: &[u8]) -> Vec<Jump> {
+    let mut moves = Vec::new();
+    let pegs = pegs & mask;
+    let holes = mask & !pegs;
+    
+    // Горизонтальные ходы
+    let can_right = holes & (holes >> 1) & (pegs >> 2);
+    let can_left = holes & (holes << 1) & (pegs << 2);
+    
+    // Вертикальные ходы
+    let can_down = holes & (holes >> 7) & (pegs >> 14);
+    let can_up = holes & (holes << 7) & (pegs << 14);
+    
+    for &pos in positions {
         // Вправо
         if (can_right >> pos) & 1 != 0 && pos % 7 <= 4 {
             moves.push((pos, pos + 1, pos + 2));
@@ -72,7 +507,7 @@ fn rust_get_moves(pegs: u64) -> PyResult<Vec<(u8, u8, u8)>> {
         // Вниз
         if (can_down >> pos) & 1 != 0 && pos / 7 <= 4 {
             let to_pos = pos + 14;
-            if to_pos <= 46 && (VALID_MASK >> to_pos) & 1 != 0 {
+            if to_pos < 49 && (mask >> to_pos) & 1 != 0 {
                 moves.push((pos, pos + 7, to_pos));
             }
         }
@@ -80,127 +515,1238 @@ fn rust_get_moves(pegs: u64) -> PyResult<Vec<(u8, u8, u8)>> {
         // Вверх
         if (can_up >> pos) & 1 != 0 && pos / 7 >= 2 {
             let to_pos = pos - 14;
-            if to_pos >= 2 && (VALID_MASK >> to_pos) & 1 != 0 {
+            if (mask >> to_pos) & 1 != 0 {
                 moves.push((pos, pos - 7, to_pos));
             }
         }
     }
     
-    Ok(moves)
+    moves
+}
+
+// Клетки, из которых прыжок вправо не переходит на следующий ряд
+pub(crate) const fn right_jump_columns(width: u8) -> u64 {
+    let mut mask = 0u64;
+    let mut pos = 0u8;
+    while pos < 64 {
+        if pos % width + 2 < width {
+            mask |= 1u64 << pos;
+        }
+        pos += 1;
+    }
+    mask
+}
+
+// Клетки, из которых прыжок влево не переходит на предыдущий ряд
+pub(crate) const fn left_jump_columns(width: u8) -> u64 {
+    let mut mask = 0u64;
+    let mut pos = 0u8;
+    while pos < 64 {
+        if pos % width >= 2 {
+            mask |= 1u64 << pos;
+        }
+        pos += 1;
+    }
+    mask
+}
+
+pub(crate) const RIGHT_JUMP_COLUMNS: u64 = right_jump_columns(7);
+pub(crate) const LEFT_JUMP_COLUMNS: u64 = left_jump_columns(7);
+
+/// Число допустимых ходов без выделения памяти
+#[pyfunction]
+fn rust_count_moves(pegs: u64) -> PyResult<u32> {
+    Ok(count_moves_on(pegs, VALID_MASK))
+}
+
+/// Вынужденный ход: единственный допустимый ход, иначе None
+#[pyfunction]
+fn rust_forced_move(pegs: u64) -> PyResult<Option<Jump>> {
+    if count_moves_on(pegs, VALID_MASK) != 1 {
+        return Ok(None);
+    }
+    Ok(generate_moves(pegs).first().copied())
+}
+
+/// Подсчёт ходов теми же масками, что в generate_moves_on, через popcount
+pub(crate) fn count_moves_on(pegs: u64, mask: u64) -> u32 {
+    let (can_right, can_left, can_down, can_up) = direction_masks_on(pegs, mask);
+    popcount64(can_right) + popcount64(can_left) + popcount64(can_down) + popcount64(can_up)
+}
+
+/// Маски клеток, откуда возможен прыжок: (вправо, влево, вниз, вверх)
+#[pyfunction]
+fn rust_direction_masks(pegs: u64) -> PyResult<(u64, u64, u64, u64)> {
+    Ok(direction_masks_on(pegs, VALID_MASK))
+}
+
+pub(crate) fn direction_masks_on(pegs: u64, mask: u64) -> (u64, u64, u64, u64) {
+    let pegs = pegs & mask;
+    let holes = mask & !pegs;
+    
+    // Горизонтальные ходы: маски столбцов убирают переход через край ряда
+    let can_right = pegs & (pegs >> 1) & (holes >> 2) & RIGHT_JUMP_COLUMNS;
+    let can_left = pegs & (pegs << 1) & (holes << 2) & LEFT_JUMP_COLUMNS;
+    
+    // Вертикальные ходы: цель берётся из holes, поэтому всегда внутри маски
+    let can_down = pegs & (pegs >> 7) & (holes >> 14);
+    let can_up = pegs & (pegs << 7) & (holes << 14);
+    
+    (can_right, can_left, can_down, can_up)
+}
+
+// Все геометрически возможные прыжки английской доски (без учёта занятости)
+pub(crate) const MOVE_TABLE: [Jump; 76] = build_move_table();
+
+const fn build_move_table() -> [Jump; 76] {
+    const STEPS: [i32; 4] = [1, -1, 7, -7];
+    let mut table = [(0u8, 0u8, 0u8); 76];
+    let mut count = 0;
+    let mut i = 0;
+    while i < VALID_POSITIONS.len() {
+        let pos = VALID_POSITIONS[i] as i32;
+        let mut d = 0;
+        while d < STEPS.len() {
+            let step = STEPS[d];
+            let (jumped, to_pos) = (pos + step, pos + 2 * step);
+            // Горизонтальный прыжок не должен переходить на другой ряд
+            let same_row = step.abs() != 1 || pos / 7 == to_pos / 7;
+            if same_row
+                && to_pos >= 0
+                && to_pos < 49
+                && (VALID_MASK >> jumped) & 1 != 0
+                && (VALID_MASK >> to_pos) & 1 != 0
+            {
+                table[count] = (pos as u8, jumped as u8, to_pos as u8);
+                count += 1;
+            }
+            d += 1;
+        }
+        i += 1;
+    }
+    assert!(count == 76);
+    table
+}
+
+/// Полная таблица прыжков английской доски (для SIMD/GPU ядер и проверок)
+#[pyfunction]
+fn rust_move_table() -> PyResult<Vec<Jump>> {
+    Ok(MOVE_TABLE.to_vec())
+}
+
+/// Однобайтовый код хода: его индекс в MOVE_TABLE (0..76)
+#[pyfunction]
+fn rust_encode_move(from_pos: u8, jumped: u8, to_pos: u8) -> PyResult<u8> {
+    let mv = (from_pos, jumped, to_pos);
+    MOVE_TABLE
+        .iter()
+        .position(|&entry| entry == mv)
+        .map(|index| index as u8)
+        .ok_or_else(|| PyValueError::new_err(format!("{:?} is not a jump on the board", mv)))
+}
+
+/// Ход по однобайтовому коду из rust_encode_move
+#[pyfunction]
+fn rust_decode_move(code: u8) -> PyResult<Jump> {
+    MOVE_TABLE.get(code as usize).copied().ok_or_else(|| {
+        PyIndexError::new_err(format!(
+            "move code {} out of range for {} jumps",
+            code,
+            MOVE_TABLE.len()
+        ))
+    })
+}
+
+/// Медленная эталонная генерация ходов для проверки rust_get_moves
+#[pyfunction]
+fn rust_get_moves_naive(pegs: u64) -> PyResult<Vec<Jump>> {
+    Ok(generate_moves_naive(pegs))
+}
+
+/// Перебор позиций и направлений с явной проверкой всех трёх клеток
+pub(crate) fn generate_moves_naive(pegs: u64) -> Vec<Jump> {
+    let on_board = |r: i32, c: i32| {
+        (0..7).contains(&r) && (0..7).contains(&c) && (VALID_MASK >> (r * 7 + c)) & 1 != 0
+    };
+    let has_peg = |pos: u8| (pegs >> pos) & 1 != 0;
+    
+    let mut moves = Vec::new();
+    for &pos in &VALID_POSITIONS {
+        let (r, c) = ((pos / 7) as i32, (pos % 7) as i32);
+        for (dr, dc) in [(0, 1), (0, -1), (1, 0), (-1, 0)] {
+            if !on_board(r + dr, c + dc) || !on_board(r + 2 * dr, c + 2 * dc) {
+                continue;
+            }
+            let jumped = ((r + dr) * 7 + c + dc) as u8;
+            let to_pos = ((r + 2 * dr) * 7 + c + 2 * dc) as u8;
+            if has_peg(pos) && has_peg(jumped) && !has_peg(to_pos) {
+                moves.push((pos, jumped, to_pos));
+            }
+        }
+    }
+    
+    moves
+}
+
+/// Ходы, упорядоченные по оценке получающейся позиции
+///
+/// Меньшая оценка rust_evaluate_position лучше; best_first = false
+/// разворачивает порядок.
+#[pyfunction]
+#[pyo3(signature = (pegs, best_first = true))]
+fn rust_get_moves_ordered(pegs: u64, best_first: bool) -> PyResult<Vec<Jump>> {
+    let mut scored: Vec<(f64, Jump)> = generate_moves(pegs)
+        .into_iter()
+        .map(|mv| {
+            let child = apply_jump(pegs, mv);
+            let num_moves = count_moves_on(child, VALID_MASK) as usize;
+            (evaluate_position(child, num_moves), mv)
+        })
+        .collect();
+    
+    scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if !best_first {
+        scored.reverse();
+    }
+    
+    Ok(scored.into_iter().map(|(_, mv)| mv).collect())
+}
+
+/// Подсказка: ход, ведущий к лучшей по оценке позиции в пределах depth ходов
+///
+/// При равных оценках выбирается первый ход в порядке rust_get_moves.
+#[pyfunction]
+fn rust_best_move(pegs: u64, depth: u32) -> PyResult<Option<Jump>> {
+    if depth == 0 {
+        return Err(PyValueError::new_err("depth must be at least 1"));
+    }
+    let mut best: Option<(f64, Jump)> = None;
+    for mv in generate_moves(pegs) {
+        let score = best_reachable_score(apply_jump(pegs, mv), depth - 1);
+        if best.is_none_or(|(best_score, _)| score < best_score) {
+            best = Some((score, mv));
+        }
+    }
+    Ok(best.map(|(_, mv)| mv))
+}
+
+// Минимальная оценка среди позиций, достижимых не более чем за depth ходов
+fn best_reachable_score(pegs: u64, depth: u32) -> f64 {
+    let mut best = evaluate_position(pegs, count_moves_on(pegs, VALID_MASK) as usize);
+    if depth > 0 {
+        for mv in generate_moves(pegs) {
+            best = best.min(best_reachable_score(apply_jump(pegs, mv), depth - 1));
+        }
+    }
+    best
+}
+
+/// Подвижность: для каждого колышка — число прыжков, которые он может сделать
+#[pyfunction]
+fn rust_peg_mobility(pegs: u64) -> PyResult<Vec<(u8, u32)>> {
+    let moves = generate_moves(pegs);
+    let mobility = VALID_POSITIONS
+        .iter()
+        .filter(|&&pos| (pegs >> pos) & 1 != 0)
+        .map(|&pos| (pos, moves.iter().filter(|mv| mv.0 == pos).count() as u32))
+        .collect();
+    
+    Ok(mobility)
+}
+
+/// Ходы, сгруппированные по прыгающему колышку: (откуда, клетки приземления)
+///
+/// generate_moves выдаёт ходы по возрастанию клетки старта, поэтому группы
+/// собираются за один проход.
+#[pyfunction]
+fn rust_moves_by_peg(pegs: u64) -> PyResult<Vec<(u8, Vec<u8>)>> {
+    let mut groups: Vec<(u8, Vec<u8>)> = Vec::new();
+    for (from_pos, _, to_pos) in generate_moves(pegs) {
+        match groups.last_mut() {
+            Some((last, targets)) if *last == from_pos => targets.push(to_pos),
+            _ => groups.push((from_pos, vec![to_pos])),
+        }
+    }
+    Ok(groups)
+}
+
+/// Почему позиция тупиковая: None, если ходы есть (или колышков <= 1)
+///
+/// Иначе — застрявшие колышки: рядом с ними нет колышка ни в одном направлении,
+/// где прыжок вообще помещается на доске. Колышки, которым мешает лишь занятая
+/// клетка приземления, в список не попадают.
+#[pyfunction]
+fn rust_dead_reason(pegs: u64) -> PyResult<Option<Vec<u8>>> {
+    if !is_dead(pegs) {
+        return Ok(None);
+    }
+    
+    let has_peg = |pos: u8| (pegs >> pos) & 1 != 0;
+    let stranded = VALID_POSITIONS
+        .iter()
+        .copied()
+        .filter(|&pos| has_peg(pos))
+        .filter(|&pos| {
+            !MOVE_TABLE
+                .iter()
+                .any(|&(from, jumped, _)| from == pos && has_peg(jumped))
+        })
+        .collect();
+    
+    Ok(Some(stranded))
+}
+
+/// Есть ли ход, после которого остаётся ровно один колышек
+///
+/// Такой ход возможен только при двух колышках, иначе сразу false.
+#[pyfunction]
+fn rust_has_winning_move(pegs: u64) -> PyResult<bool> {
+    Ok(popcount64(pegs) == 2 && count_moves_on(pegs, VALID_MASK) > 0)
+}
+
+/// Ходы вместе с позициями, которые получаются после их применения
+#[pyfunction]
+fn rust_get_children(pegs: u64) -> PyResult<Vec<(u8, u8, u8, u64)>> {
+    let children = generate_moves(pegs)
+        .into_iter()
+        .map(|mv| (mv.0, mv.1, mv.2, apply_jump(pegs, mv)))
+        .collect();
+    
+    Ok(children)
+}
+
+/// Batch генерация ходов для нескольких позиций за один вызов
+#[pyfunction]
+fn rust_get_moves_batch(pegs_list: Vec<u64>) -> PyResult<Vec<Vec<Jump>>> {
+    let mut results = Vec::with_capacity(pegs_list.len());
+    
+    for &pegs in &pegs_list {
+        results.push(generate_moves(pegs));
+    }
+    
+    Ok(results)
 }
 
 /// Проверка тупика: нет ходов, но > 1 колышка
+///
+/// Пустая доска и доска с одним колышком тупиком не считаются.
 #[pyfunction]
 fn rust_is_dead(pegs: u64) -> PyResult<bool> {
+    Ok(is_dead(pegs))
+}
+
+pub(crate) fn is_dead(pegs: u64) -> bool {
+    is_dead_fixed::<7, VALID_MASK>(pegs)
+}
+
+/// Проверка тупика с шириной и маской, известными при компиляции
+pub(crate) fn is_dead_fixed<const WIDTH: u8, const MASK: u64>(pegs: u64) -> bool {
+    is_dead_grid(
+        pegs,
+        MASK,
+        WIDTH,
+        const { right_jump_columns(WIDTH) },
+        const { left_jump_columns(WIDTH) },
+    )
+}
+
+/// Проверка тупика на сетке произвольной ширины
+///
+/// right_columns/left_columns отсекают прыжки через край ряда
+/// (см. right_jump_columns и left_jump_columns).
+pub(crate) fn is_dead_grid(
+    pegs: u64,
+    mask: u64,
+    width: u8,
+    right_columns: u64,
+    left_columns: u64,
+) -> bool {
     let count = popcount64(pegs);
     if count <= 1 {
-        return Ok(false);
+        return false;
     }
     
-    let holes = VALID_MASK & !pegs;
+    let pegs = pegs & mask;
+    let holes = mask & !pegs;
+    let row = width as u32;
     
     // Проверяем, есть ли хоть один ход
-    let can_right = pegs & (pegs >> 1) & (holes >> 2);
+    let can_right = pegs & (pegs >> 1) & (holes >> 2) & right_columns;
     if can_right != 0 {
-        return Ok(false);
+        return false;
     }
     
-    let can_left = pegs & (pegs << 1) & (holes << 2);
+    let can_left = pegs & (pegs << 1) & (holes << 2) & left_columns;
     if can_left != 0 {
-        return Ok(false);
+        return false;
     }
     
-    let can_down = pegs & (pegs >> 7) & (holes >> 14);
+    let can_down = pegs & (pegs >> row) & (holes >> (2 * row));
     if can_down != 0 {
-        return Ok(false);
+        return false;
     }
     
-    let can_up = pegs & (pegs << 7) & (holes << 14);
+    let can_up = pegs & (pegs << row) & (holes << (2 * row));
     if can_up != 0 {
-        return Ok(false);
+        return false;
     }
     
-    Ok(true)
+    true
 }
 
 /// Pagoda функция (быстрая Rust версия)
 #[pyfunction]
 fn rust_pagoda_value(pegs: u64) -> PyResult<u32> {
-    let weights: [u32; 33] = [
-        1, 2, 1,  // 2, 3, 4
-        2, 4, 2,  // 9, 10, 11
-        1, 2, 3, 4, 3, 2, 1,  // 14-20
-        2, 4, 4, 6, 4, 4, 2,  // 21-27
-        1, 2, 3, 4, 3, 2, 1,  // 28-34
-        2, 4, 2,  // 37, 38, 39
-        1, 2, 1,  // 44, 45, 46
-    ];
-    
+    Ok(pagoda_value(pegs))
+}
+
+pub(crate) fn pagoda_value(pegs: u64) -> u32 {
+    pagoda_on(pegs, &VALID_POSITIONS, &PAGODA_WEIGHTS)
+}
+
+/// Изменение pagoda значения при прыжке: -w[from] - w[jumped] + w[to]
+#[pyfunction]
+fn rust_pagoda_delta(from_pos: u8, jumped: u8, to_pos: u8) -> PyResult<i32> {
+    let weight = |pos: u8| rust_pos_to_index(pos).map(|i| PAGODA_WEIGHTS[i] as i32);
+    Ok(weight(to_pos)? - weight(from_pos)? - weight(jumped)?)
+}
+
+/// Pagoda функция с пользовательскими весами (параллельно VALID_POSITIONS)
+#[pyfunction]
+fn rust_pagoda_value_custom(pegs: u64, weights: [u32; 33]) -> PyResult<u32> {
+    Ok(pagoda_on(pegs, &VALID_POSITIONS, &weights))
+}
+
+// Числа Фибоначчи для pagoda весов вокруг произвольной цели
+const FIB: [u32; 14] = [1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233, 377];
+
+/// Pagoda вес клетки относительно цели: F(13 - манхэттенское расстояние)
+///
+/// Для прыжка a, b -> c к цели w(a) + w(b) = w(c), в остальных случаях
+/// w(c) не больше, так что сумма не растёт, а у цели вес максимален.
+fn target_pagoda_weight(pos: u8, target_pos: u8) -> u32 {
+    let dr = (pos / 7).abs_diff(target_pos / 7);
+    let dc = (pos % 7).abs_diff(target_pos % 7);
+    FIB[FIB.len() - 1 - (dr + dc) as usize]
+}
+
+/// Цель недостижима: pagoda сумма с весами вокруг target_pos меньше веса цели
+#[pyfunction]
+fn rust_target_unreachable(pegs: u64, target_pos: u8) -> PyResult<bool> {
+    check_target(target_pos)?;
+    Ok(target_unreachable(pegs, target_pos))
+}
+
+/// ValueError, если целевая клетка не лежит на доске
+pub(crate) fn check_target(target_pos: u8) -> PyResult<()> {
+    if target_pos >= 49 || (VALID_MASK >> target_pos) & 1 == 0 {
+        return Err(PyValueError::new_err(format!(
+            "target position {} is not on the board",
+            target_pos
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn target_unreachable(pegs: u64, target_pos: u8) -> bool {
+    let total: u32 = VALID_POSITIONS
+        .iter()
+        .filter(|&&pos| (pegs >> pos) & 1 != 0)
+        .map(|&pos| target_pagoda_weight(pos, target_pos))
+        .sum();
+    total < target_pagoda_weight(target_pos, target_pos)
+}
+
+/// Pagoda сумма для произвольного набора позиций и весов
+pub(crate) fn pagoda_on(pegs: u64, positions: &[u8], weights: &[u32]) -> u32 {
     let mut total = 0u32;
-    for (i, &pos) in VALID_POSITIONS.iter().enumerate() {
+    for (i, &pos) in positions.iter().enumerate() {
         if (pegs >> pos) & 1 != 0 {
             total += weights[i];
         }
     }
     
-    Ok(total)
+    total
 }
 
-/// Быстрая оценка позиции (Rust версия)
+/// Число колышков в каждом из 7 рядов (клетки вне доски не учитываются)
 #[pyfunction]
-fn rust_evaluate_position(pegs: u64, num_moves: usize) -> PyResult<f64> {
-    let peg_count = popcount64(pegs) as f64;
-    let mut distance_sum = 0u32;
-    let center_row = 3u8;
-    let center_col = 3u8;
-    
+fn rust_row_counts(pegs: u64) -> PyResult<Vec<u32>> {
+    Ok(line_counts(pegs, |pos| pos / 7).to_vec())
+}
+
+/// Число колышков в каждом из 7 столбцов (клетки вне доски не учитываются)
+#[pyfunction]
+fn rust_col_counts(pegs: u64) -> PyResult<Vec<u32>> {
+    Ok(line_counts(pegs, |pos| pos % 7).to_vec())
+}
+
+// Раскладывает колышки валидных клеток по линиям сетки 7x7
+fn line_counts(pegs: u64, line_of: impl Fn(u8) -> u8) -> [u32; 7] {
+    let mut counts = [0u32; 7];
     for &pos in &VALID_POSITIONS {
         if (pegs >> pos) & 1 != 0 {
-            let r = pos / 7;
-            let c = pos % 7;
-            distance_sum += (r as i32 - center_row as i32).abs() as u32 +
-                           (c as i32 - center_col as i32).abs() as u32;
+            counts[line_of(pos) as usize] += 1;
         }
     }
+    counts
+}
+
+// Угловые клетки рукавов: клетки VALID_POSITIONS вне центрального квадрата 3x3,
+// не лежащие на центральном ряду или столбце — по 4 на рукав
+// (2, 4, 9, 11 / 14, 15, 28, 29 / 19, 20, 33, 34 / 37, 39, 44, 46)
+const CORNER_MASK: u64 = build_corner_mask();
+
+const fn build_corner_mask() -> u64 {
+    let mut mask = 0u64;
+    let mut i = 0;
+    while i < VALID_POSITIONS.len() {
+        let pos = VALID_POSITIONS[i];
+        let (r, c) = (pos / 7, pos % 7);
+        let in_arm = r < 2 || r > 4 || c < 2 || c > 4;
+        if in_arm && r != 3 && c != 3 {
+            mask |= 1u64 << pos;
+        }
+        i += 1;
+    }
+    mask
+}
+
+/// Колышки в угловых клетках рукавов — их труднее всего убрать
+#[pyfunction]
+fn rust_corner_pegs(pegs: u64) -> PyResult<u32> {
+    Ok(popcount64(pegs & CORNER_MASK))
+}
+
+/// Одинокие колышки: ни одного соседнего колышка по горизонтали или вертикали
+///
+/// Такой колышек не может ни прыгнуть, ни быть съеденным, пока к нему не подойдут.
+#[pyfunction]
+fn rust_isolated_pegs(pegs: u64) -> PyResult<u32> {
+    Ok(isolated_pegs(pegs))
+}
+
+pub(crate) fn isolated_pegs(pegs: u64) -> u32 {
+    let pegs = pegs & VALID_MASK;
+    let has_peg = |pos: u8| (pegs >> pos) & 1 != 0;
     
-    let mut score = peg_count * 10.0 + distance_sum as f64;
-    score -= num_moves as f64 * 2.0;
-    
-    // Pagoda проверка
-    let pagoda_val = rust_pagoda_value(pegs).unwrap();
-    let target_pagoda = 6u32; // CENTER_POS = 24, weight = 6
-    
-    if peg_count > 15.0 {
-        if pagoda_val < target_pagoda {
-            score += 1000.0;
+    let mut count = 0u32;
+    for &pos in &VALID_POSITIONS {
+        if !has_peg(pos) {
+            continue;
+        }
+        let col = pos % 7;
+        let neighbor = (col < 6 && has_peg(pos + 1))
+            || (col > 0 && has_peg(pos - 1))
+            || (pos < 42 && has_peg(pos + 7))
+            || (pos >= 7 && has_peg(pos - 7));
+        if !neighbor {
+            count += 1;
         }
     }
     
-    Ok(score)
+    count
 }
 
-/// Batch оценка нескольких позиций (параллельная обработка)
+/// Лунки, в которые сейчас не может прийти колышек: ни с одной стороны нет колышка
+/// на соседней клетке или через одну
+///
+/// Приближение: колышки могут подойти позже, поэтому это признак для оценки, а не доказательство.
 #[pyfunction]
-fn rust_evaluate_batch(pegs_list: Vec<u64>, moves_list: Vec<usize>) -> PyResult<Vec<f64>> {
-    let mut results = Vec::with_capacity(pegs_list.len());
+fn rust_unfillable_holes(pegs: u64) -> PyResult<u32> {
+    Ok(unfillable_holes(pegs))
+}
+
+pub(crate) fn unfillable_holes(pegs: u64) -> u32 {
+    let pegs = pegs & VALID_MASK;
+    let has_peg = |pos: u8| (pegs >> pos) & 1 != 0;
+    // Клетка доски по (ряд, столбец) со знаком; None вне сетки или вне креста
+    let cell = |r: i32, c: i32| {
+        if !ENGLISH_GEOMETRY.contains(r, c) {
+            return None;
+        }
+        let pos = ENGLISH_GEOMETRY.from_rc(r as u8, c as u8);
+        is_valid_position(pos).then_some(pos)
+    };
+
+    let mut count = 0u32;
+    for &pos in &VALID_POSITIONS {
+        if has_peg(pos) {
+            continue;
+        }
+        let (row, col) = ENGLISH_GEOMETRY.to_rc(pos);
+        let (row, col) = (row as i32, col as i32);
+        let reachable = [(0, 1), (0, -1), (1, 0), (-1, 0)].iter().any(|&(dr, dc)| {
+            match (cell(row + dr, col + dc), cell(row + 2 * dr, col + 2 * dc)) {
+                (Some(near), Some(far)) => has_peg(near) || has_peg(far),
+                _ => false,
+            }
+        });
+        if !reachable {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Быстрая оценка позиции (Rust версия)
+#[pyfunction]
+fn rust_evaluate_position(pegs: u64, num_moves: usize) -> PyResult<f64> {
+    Ok(evaluate_position(pegs, num_moves))
+}
+
+/// Разброс: сумма манхэттенских расстояний колышков до центра (3, 3)
+#[pyfunction]
+fn rust_spread(pegs: u64) -> PyResult<u32> {
+    Ok(spread(pegs))
+}
+
+pub(crate) fn spread(pegs: u64) -> u32 {
+    VALID_POSITIONS
+        .iter()
+        .filter(|&&pos| (pegs >> pos) & 1 != 0)
+        .map(|&pos| center_distance(pos))
+        .sum()
+}
+
+/// Манхэттенское расстояние клетки до центра (3, 3)
+pub(crate) fn center_distance(pos: u8) -> u32 {
+    let (center_row, center_col) = ENGLISH_GEOMETRY.center();
+    let (r, c) = ENGLISH_GEOMETRY.to_rc(pos);
+    r.abs_diff(center_row) as u32 + c.abs_diff(center_col) as u32
+}
+
+/// Коэффициенты оценки позиции
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct EvalWeights {
+    pub(crate) peg_weight: f64,
+    pub(crate) spread_weight: f64,
+    pub(crate) move_weight: f64,
+    pub(crate) pagoda_penalty: f64,
+    pub(crate) pagoda_threshold: f64,
+}
+
+// Коэффициенты rust_evaluate_position
+pub(crate) const DEFAULT_EVAL_WEIGHTS: EvalWeights = EvalWeights {
+    peg_weight: 10.0,
+    spread_weight: 1.0,
+    move_weight: 2.0,
+    pagoda_penalty: 1000.0,
+    pagoda_threshold: 15.0,
+};
+
+/// Оценка позиции с настраиваемыми коэффициентами
+#[pyfunction]
+fn rust_evaluate_position_weighted(
+    pegs: u64,
+    num_moves: usize,
+    peg_weight: f64,
+    spread_weight: f64,
+    move_weight: f64,
+    pagoda_penalty: f64,
+    pagoda_threshold: f64,
+) -> PyResult<f64> {
+    let weights = EvalWeights {
+        peg_weight,
+        spread_weight,
+        move_weight,
+        pagoda_penalty,
+        pagoda_threshold,
+    };
+    Ok(evaluate_position_weighted(pegs, num_moves, &weights))
+}
+
+pub(crate) fn evaluate_position(pegs: u64, num_moves: usize) -> f64 {
+    evaluate_position_weighted(pegs, num_moves, &DEFAULT_EVAL_WEIGHTS)
+}
+
+pub(crate) fn evaluate_position_weighted(pegs: u64, num_moves: usize, weights: &EvalWeights) -> f64 {
+    let peg_count = popcount64(pegs) as f64;
+    let mut score = peg_count * weights.peg_weight + spread(pegs) as f64 * weights.spread_weight;
+    score -= num_moves as f64 * weights.move_weight;
     
-    for (pegs, &num_moves) in pegs_list.iter().zip(moves_list.iter()) {
-        results.push(rust_evaluate_position(*pegs, num_moves).unwrap());
+    // Pagoda проверка
+    let pagoda_val = pagoda_value(pegs);
+    if peg_count > weights.pagoda_threshold && pagoda_val < PAGODA_TARGET {
+        score += weights.pagoda_penalty;
     }
     
-    Ok(results)
+    score
+}
+
+/// Batch оценка нескольких позиций (параллельная обработка через rayon)
+///
+/// Порядок результатов совпадает с порядком входа.
+#[pyfunction]
+fn rust_evaluate_batch(pegs_list: Vec<u64>, moves_list: Vec<usize>) -> PyResult<Vec<f64>> {
+    if moves_list.len() != pegs_list.len() {
+        return Err(PyValueError::new_err(format!(
+            "pegs_list and moves_list have different lengths: {} and {}",
+            pegs_list.len(),
+            moves_list.len()
+        )));
+    }
+    Ok(evaluate_batch(&pegs_list, &moves_list))
+}
+
+/// Batch оценка через buffer protocol (numpy uint64, array.array('Q') и т.п.)
+///
+/// Без moves число ходов считается для каждой позиции; moves принимает и знаковые
+/// (numpy int64 по умолчанию), и беззнаковые буферы. Непрерывные буферы читаются
+/// без копии. Результат — array.array('d'), numpy читает его через np.frombuffer.
+#[pyfunction]
+#[pyo3(signature = (pegs, moves=None))]
+fn rust_evaluate_array<'py>(
+    py: Python<'py>,
+    pegs: PyBuffer<u64>,
+    moves: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let pegs_list = buffer_slice(py, &pegs)?;
+    // GIL держится всё время: чужую память буфера не должен менять другой поток
+    let scores = match moves {
+        Some(moves) => match PyBuffer::<i64>::get_bound(moves) {
+            Ok(signed) => evaluate_with_counts(&pegs_list, &buffer_slice(py, &signed)?)?,
+            Err(_) => {
+                let unsigned = PyBuffer::<u64>::get_bound(moves)?;
+                evaluate_with_counts(&pegs_list, &buffer_slice(py, &unsigned)?)?
+            }
+        },
+        None => pegs_list
+            .par_iter()
+            .map(|&p| evaluate_position(p, count_moves_on(p, VALID_MASK) as usize))
+            .collect(),
+    };
+
+    let bytes: Vec<u8> = scores.iter().flat_map(|score| score.to_ne_bytes()).collect();
+    py.import_bound("array")?
+        .getattr("array")?
+        .call1(("d", PyBytes::new_bound(py, &bytes)))
+}
+
+// Содержимое буфера: C-непрерывный читается на месте, остальные копируются
+fn buffer_slice<'a, T: Element + Copy>(
+    py: Python<'a>,
+    buffer: &'a PyBuffer<T>,
+) -> PyResult<Cow<'a, [T]>> {
+    match buffer.as_slice(py) {
+        // SAFETY: ReadOnlyCell<T> — repr(transparent) над T, а память буфера
+        // экспортирована и живёт не меньше, чем PyBuffer
+        Some(cells) => Ok(Cow::Borrowed(unsafe {
+            slice::from_raw_parts(cells.as_ptr().cast::<T>(), cells.len())
+        })),
+        None => Ok(Cow::Owned(buffer.to_vec(py)?)),
+    }
+}
+
+// Оценка с числом ходов из буфера; отрицательные числа ходов отклоняются
+fn evaluate_with_counts<M>(pegs_list: &[u64], moves_list: &[M]) -> PyResult<Vec<f64>>
+where
+    M: Copy + Sync,
+    usize: TryFrom<M>,
+{
+    if moves_list.len() != pegs_list.len() {
+        return Err(PyValueError::new_err(format!(
+            "pegs and moves have different lengths: {} and {}",
+            pegs_list.len(),
+            moves_list.len()
+        )));
+    }
+    if moves_list.iter().any(|&n| usize::try_from(n).is_err()) {
+        return Err(PyValueError::new_err("move counts must be non-negative"));
+    }
+
+    Ok(pegs_list
+        .par_iter()
+        .zip(moves_list.par_iter())
+        .map(|(&pegs, &n)| evaluate_position(pegs, usize::try_from(n).unwrap_or(0)))
+        .collect())
+}
+
+pub(crate) fn evaluate_batch(pegs_list: &[u64], moves_list: &[usize]) -> Vec<f64> {
+    pegs_list
+        .par_iter()
+        .zip(moves_list.par_iter())
+        .map(|(&pegs, &num_moves)| evaluate_position(pegs, num_moves))
+        .collect()
 }
 
 #[pymodule]
 fn rust_peg_solver(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("VALID_POSITIONS", VALID_POSITIONS.to_vec())?;
+    m.add("VALID_MASK", VALID_MASK)?;
+    m.add_class::<board::Board>()?;
+    m.add_class::<board::CustomBoard>()?;
+    m.add_class::<game::GameState>()?;
+    m.add_class::<geometry::BoardGeometry>()?;
+    m.add_class::<moves::Move>()?;
+    m.add_class::<move_iter::MoveIterator>()?;
     m.add_function(wrap_pyfunction!(rust_peg_count, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_peg_count_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_min_moves_remaining, m)?)?;
     m.add_function(wrap_pyfunction!(rust_has_peg, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_pos_to_index, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_index_to_pos, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_holes, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_complement, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_standard_start, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_is_solved, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_progress, m)?)?;
     m.add_function(wrap_pyfunction!(rust_apply_move, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_apply_move_index, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_step, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_undo_move, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_diff, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_validate_sequence, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_is_valid_board, m)?)?;
     m.add_function(wrap_pyfunction!(rust_get_moves, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_get_moves_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_get_moves_naive, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_get_moves_masked, m)?)?;
+    m.add_function(wrap_pyfunction!(moves::rust_get_moves_typed, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_center_biased_moves, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_get_moves_ordered, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_best_move, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_move_table, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_encode_move, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_decode_move, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_count_moves, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_forced_move, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_direction_masks, m)?)?;
+    m.add_function(wrap_pyfunction!(move_iter::rust_iter_moves, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_get_children, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_peg_mobility, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_moves_by_peg, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_get_reverse_moves, m)?)?;
     m.add_function(wrap_pyfunction!(rust_is_dead, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_has_winning_move, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_dead_reason, m)?)?;
     m.add_function(wrap_pyfunction!(rust_pagoda_value, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_pagoda_value_custom, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_pagoda_delta, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_target_unreachable, m)?)?;
+    m.add_function(wrap_pyfunction!(invariants::rust_provably_unsolvable, m)?)?;
+    m.add_function(wrap_pyfunction!(invariants::rust_pagoda_screen, m)?)?;
+    m.add_function(wrap_pyfunction!(invariants::rust_split_unsolvable, m)?)?;
+    m.add_function(wrap_pyfunction!(invariants::rust_color_invariant, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_spread, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_isolated_pegs, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_unfillable_holes, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_row_counts, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_col_counts, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_corner_pegs, m)?)?;
     m.add_function(wrap_pyfunction!(rust_evaluate_position, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_evaluate_position_weighted, m)?)?;
     m.add_function(wrap_pyfunction!(rust_evaluate_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_evaluate_array, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solution_boards, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_greedy, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_to, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_reachable_finishes, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_ida, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_parallel, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solvable_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_undo_to_solvable, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_memoized, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_all, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_count_solutions, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solution_length_histogram, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_deadend_census, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_collect_deadends, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_reachable_within, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_count_reachable, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_can_reach, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_build_tablebase, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_perft, m)?)?;
+    m.add_function(wrap_pyfunction!(solver::rust_solve_bidirectional, m)?)?;
+    m.add_function(wrap_pyfunction!(playout::rust_random_playout, m)?)?;
+    m.add_function(wrap_pyfunction!(playout::rust_random_game, m)?)?;
+    m.add_function(wrap_pyfunction!(playout::rust_sample_solvable, m)?)?;
+    m.add_function(wrap_pyfunction!(playout::rust_guided_playout, m)?)?;
+    m.add_function(wrap_pyfunction!(zobrist::rust_zobrist_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(zobrist::rust_state_key, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_rotate90, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_mirror_horizontal, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_mirror_diagonal, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_canonical, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_canonical_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_dedup_canonical, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_all_symmetries, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_orbit_size, m)?)?;
+    m.add_function(wrap_pyfunction!(symmetry::rust_symmetry_reduced_moves, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_render, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_parse, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_move_notation, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_parse_notation, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_to_base64, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_from_base64, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_to_bitstring, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_from_bitstring, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_serialize_game, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_deserialize_game, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_to_external_index, m)?)?;
+    m.add_function(wrap_pyfunction!(peg_io::rust_from_external_index, m)?)?;
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Детерминированный поток случайных слов для перебора досок
+    fn random_words(seed: u64, count: usize) -> impl Iterator<Item = u64> {
+        let mut rng = playout::XorShift64::new(seed);
+        (0..count).map(move |_| rng.next_u64())
+    }
+
+    fn sorted(mut moves: Vec<Jump>) -> Vec<Jump> {
+        moves.sort_unstable();
+        moves
+    }
+
+    #[test]
+    fn generated_moves_match_naive() {
+        for state in random_words(0x2545_F491_4F6C_DD1D, 20_000) {
+            // И валидные доски, и доски с мусором вне маски
+            for pegs in [state & VALID_MASK, state] {
+                assert_eq!(
+                    sorted(generate_moves(pegs)),
+                    sorted(generate_moves_naive(pegs)),
+                    "pegs = {:#x}",
+                    pegs
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn move_iterator_matches_generate_moves() {
+        for state in random_words(0xD1B5_4A32_D192_ED03, 20_000) {
+            for pegs in [state & VALID_MASK, state] {
+                let iter = move_iter::MoveIterator::new(pegs);
+                assert_eq!(iter.size_hint().0, generate_moves(pegs).len());
+                assert_eq!(
+                    sorted(iter.collect()),
+                    sorted(generate_moves(pegs)),
+                    "pegs = {:#x}",
+                    pegs
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_dead_matches_move_generation() {
+        // Полный ряд 14..=20: прыжок 19 -> 21 через край ряда не считается ходом
+        assert!(is_dead(0b1111111u64 << 14));
+
+        for state in random_words(0x9E37_79B9_7F4A_7C15, 20_000) {
+            // Разреженные доски чаще оказываются тупиками
+            for pegs in [state & VALID_MASK, state & (state >> 17) & VALID_MASK] {
+                let expected = popcount64(pegs) > 1 && generate_moves(pegs).is_empty();
+                assert_eq!(is_dead(pegs), expected, "pegs = {:#x}", pegs);
+            }
+        }
+    }
+
+    #[test]
+    fn english_is_dead_ignores_row_wraparound() {
+        // Соседние биты на стыке рядов: прыжки 20 -> 22, 21 -> 19, 27 -> 29 и
+        // 28 -> 26 уходят через край ряда и ходами не являются
+        for pair in [(20, 21), (27, 28)] {
+            let pegs = (1u64 << pair.0) | (1u64 << pair.1);
+            assert!(generate_moves(pegs).is_empty(), "pegs = {:?}", pair);
+            assert!(is_dead(pegs), "pegs = {:?}", pair);
+        }
+
+        // Полные средние ряды: ходы возможны только через край ряда
+        for row in 2..5 {
+            let full_row = 0b1111111u64 << (row * 7);
+            assert!(generate_moves(full_row).is_empty(), "row = {}", row);
+            assert!(is_dead(full_row), "row = {}", row);
+        }
+    }
+
+    #[test]
+    fn fixed_width_generation_matches_grid() {
+        // Вся сетка 7x7 и доска с дырой в центре проверяют края рядов и столбцов
+        const FULL_GRID: u64 = (1 << 49) - 1;
+        const HOLED: u64 = FULL_GRID & !(1 << 24);
+        let grid_positions: Vec<u8> = (0..49).collect();
+
+        for state in random_words(0x9B05_688C_2B3E_6C1F, 20_000) {
+            for pegs in [state & FULL_GRID, state & (state >> 23) & FULL_GRID] {
+                assert_eq!(
+                    generate_moves_fixed::<7, FULL_GRID>(pegs),
+                    generate_moves_grid(pegs, FULL_GRID, &grid_positions, 7),
+                    "pegs = {:#x}",
+                    pegs
+                );
+                assert_eq!(
+                    is_dead_fixed::<7, HOLED>(pegs & HOLED),
+                    is_dead_grid(pegs & HOLED, HOLED, 7, RIGHT_JUMP_COLUMNS, LEFT_JUMP_COLUMNS)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn diagonal_mirror_is_a_board_symmetry() {
+        use crate::symmetry::{all_symmetries, mirror_diagonal};
+
+        for state in random_words(0x510E_527F_ADE6_82D1, 1_000) {
+            let pegs = state & VALID_MASK;
+            let image = mirror_diagonal(pegs);
+            assert_eq!(mirror_diagonal(image), pegs);
+            assert_eq!(popcount64(image), popcount64(pegs));
+            assert!(all_symmetries(pegs).contains(&image));
+        }
+    }
+
+    #[test]
+    fn valid_mask_is_exactly_the_cross() {
+        // Исходная маска теряла клетки 3, 4, 9..11, 17..20, 24..27 и брала биты вне креста
+        let expected = VALID_POSITIONS.iter().fold(0u64, |mask, &pos| mask | 1u64 << pos);
+        assert_eq!(VALID_MASK, expected);
+        assert_eq!(popcount64(VALID_MASK), 33);
+    }
+
+    #[test]
+    fn position_index_inverts_valid_positions() {
+        for (i, &pos) in VALID_POSITIONS.iter().enumerate() {
+            assert_eq!(position_index(pos), Some(i));
+        }
+        for pos in [0, 1, 5, 48, 63, 64, 255] {
+            assert_eq!(position_index(pos), None);
+        }
+    }
+
+    #[test]
+    fn positions_outside_the_board_are_rejected() {
+        for pos in [64, 255, 0, 1, 48] {
+            assert!(!is_valid_position(pos), "position {} accepted", pos);
+        }
+        for &pos in &VALID_POSITIONS {
+            assert!(is_valid_position(pos));
+        }
+    }
+
+    #[test]
+    fn evaluation_survives_adversarial_inputs() {
+        let boards = [0, u64::MAX, !VALID_MASK, 1 << 63, VALID_MASK];
+        let moves = [usize::MAX, 0, 1, usize::MAX / 2, 76];
+
+        let scores = evaluate_batch(&boards, &moves);
+        assert_eq!(scores.len(), boards.len());
+        assert!(scores.iter().all(|score| score.is_finite()));
+
+        for &pegs in &boards {
+            assert!(evaluate_position(pegs, 0).is_finite());
+            assert!(spread(pegs) <= spread(VALID_MASK));
+            assert!(isolated_pegs(pegs) <= 33);
+            assert!(unfillable_holes(pegs) <= 33);
+            assert_eq!(
+                count_moves_on(pegs, VALID_MASK) as usize,
+                generate_moves(pegs & VALID_MASK).len()
+            );
+        }
+    }
+
+    #[test]
+    fn parallel_evaluate_batch_matches_sequential() {
+        let mut pegs_list = Vec::new();
+        let mut moves_list = Vec::new();
+        for (i, state) in random_words(0x5851_F42D_4C95_7F2D, 10_000).enumerate() {
+            pegs_list.push(state & VALID_MASK);
+            moves_list.push(i % 20);
+        }
+
+        let sequential: Vec<f64> = pegs_list
+            .iter()
+            .zip(&moves_list)
+            .map(|(&pegs, &num_moves)| evaluate_position(pegs, num_moves))
+            .collect();
+        let parallel = evaluate_batch(&pegs_list, &moves_list);
+
+        assert_eq!(parallel.len(), sequential.len());
+        for (p, s) in parallel.iter().zip(&sequential) {
+            assert_eq!(p.to_bits(), s.to_bits());
+        }
+    }
+
+    #[test]
+    fn empty_and_single_peg_boards_have_no_moves() {
+        assert!(generate_moves(0).is_empty());
+        assert!(!is_dead(0));
+        assert_eq!(count_moves_on(0, VALID_MASK), 0);
+
+        for &pos in &VALID_POSITIONS {
+            let pegs = 1u64 << pos;
+            assert!(generate_moves(pegs).is_empty(), "pos = {}", pos);
+            assert!(!is_dead(pegs), "pos = {}", pos);
+            assert_eq!(count_moves_on(pegs, VALID_MASK), 0);
+        }
+    }
+
+    #[test]
+    fn provably_unsolvable_never_rejects_a_solvable_position() {
+        let mut rejected = 0;
+        for state in random_words(0x1F83_D9AB_FB41_BD6B, 3_000) {
+            // До 8 колышков — DFS отвечает мгновенно
+            let pegs = state & (state >> 11) & (state >> 23) & VALID_MASK;
+            if popcount64(pegs) > 8 {
+                continue;
+            }
+            if invariants::provably_unsolvable(pegs) {
+                rejected += 1;
+                assert!(solver::solve_core(pegs).is_none(), "pegs = {:#x}", pegs);
+            }
+        }
+        assert!(rejected > 0);
+
+        let start = VALID_MASK ^ (1u64 << 24);
+        assert!(!invariants::provably_unsolvable(start));
+    }
+
+    #[test]
+    fn solve_ida_agrees_with_solve_core() {
+        // Финиш в клетке 4, а не в центре
+        let off_centre = (1u64 << 2) | (1u64 << 3);
+        assert_eq!(solver::solve_ida(off_centre, 1_000), Some(vec![(2, 3, 4)]));
+
+        for state in random_words(0xBB67_AE85_84CA_A73B, 3_000) {
+            let pegs = state & (state >> 11) & (state >> 23) & VALID_MASK;
+            if popcount64(pegs) > 8 {
+                continue;
+            }
+            let found = solver::solve_ida(pegs, usize::MAX);
+            assert_eq!(found.is_some(), solver::solve_core(pegs).is_some(), "pegs = {:#x}", pegs);
+            if let Some(path) = found {
+                let last = path.iter().fold(pegs, |board, &mv| apply_jump(board, mv));
+                assert_eq!(popcount64(last), 1, "pegs = {:#x}", pegs);
+            }
+        }
+    }
+
+    #[test]
+    fn solution_count_does_not_depend_on_symmetry() {
+        assert_eq!(solver::count_solutions((1u64 << 2) | (1u64 << 3)), 1);
+
+        for state in random_words(0x3C6E_F372_FE94_F82B, 300) {
+            let pegs = state & (state >> 9) & VALID_MASK;
+            if popcount64(pegs) > 12 {
+                continue;
+            }
+            assert_eq!(
+                solver::count_solutions(pegs),
+                solver::count_canonical_solutions(pegs),
+                "pegs = {:#x}",
+                pegs
+            );
+        }
+    }
+
+    #[test]
+    fn terminal_census_finishes_on_the_centre_start() {
+        let census = solver::terminal_census(VALID_MASK ^ (1u64 << 24));
+        // Все партии с центрального старта не помещаются в u64
+        let games: u128 = census.values().sum();
+        assert_eq!(games, 577_116_156_815_309_849_672);
+        assert_eq!(census[&1], 81_723_294_080_159_936);
+    }
+
+    #[test]
+    fn deadend_census_skips_single_peg_finishes() {
+        // Пара в рукаве решается; две пары в противоположных рукавах дают
+        // два порядка ходов, оба заканчиваются двумя колышками
+        let pair = (1u64 << 2) | (1u64 << 3);
+        assert!(solver::deadend_census(pair).is_empty());
+        assert_eq!(solver::deadend_census(pair | (1u64 << 44) | (1u64 << 45)), [(2, 2)]);
+    }
+
+    #[test]
+    fn terminal_census_matches_solution_count() {
+        for word in random_words(0xA54F_F53A_5F1D_36F1, 300) {
+            let pegs = word & (word >> 9) & VALID_MASK;
+            if popcount64(pegs) > 12 {
+                continue;
+            }
+            let census = solver::terminal_census(pegs);
+            let solutions = census.get(&1).copied().unwrap_or(0);
+            assert_eq!(solutions, solver::count_solutions(pegs) as u128, "pegs = {:#x}", pegs);
+        }
+    }
+
+    #[test]
+    fn split_unsolvable_never_rejects_a_solvable_position() {
+        // Две пары в противоположных рукавах ходить могут, но встретиться — нет
+        let arms = (1u64 << 2) | (1u64 << 3) | (1u64 << 44) | (1u64 << 45);
+        assert!(!is_dead(arms));
+        assert!(invariants::split_unsolvable(arms));
+
+        for state in random_words(0x6A09_E667_F3BC_C909, 3_000) {
+            let pegs = state & (state >> 11) & (state >> 23) & VALID_MASK;
+            if popcount64(pegs) <= 8 && invariants::split_unsolvable(pegs) {
+                assert!(solver::solve_core(pegs).is_none(), "pegs = {:#x}", pegs);
+            }
+        }
+        assert!(!invariants::split_unsolvable(VALID_MASK ^ (1u64 << 24)));
+    }
+
+    #[test]
+    fn move_table_filtered_by_occupancy_matches_generate_moves() {
+        for state in random_words(0xA076_1D64_78BD_642F, 20_000) {
+            let pegs = state & VALID_MASK;
+            let has_peg = |pos: u8| (pegs >> pos) & 1 != 0;
+            let from_table: Vec<Jump> = MOVE_TABLE
+                .iter()
+                .copied()
+                .filter(|&(from, jumped, to)| has_peg(from) && has_peg(jumped) && !has_peg(to))
+                .collect();
+            assert_eq!(sorted(from_table), sorted(generate_moves(pegs)), "pegs = {:#x}", pegs);
+        }
+    }
+
+    #[test]
+    fn corner_mask_has_four_cells_per_arm() {
+        let expected = [2, 4, 9, 11, 14, 15, 19, 20, 28, 29, 33, 34, 37, 39, 44, 46];
+        let mask = expected.iter().fold(0u64, |mask, &pos| mask | 1u64 << pos);
+        assert_eq!(CORNER_MASK, mask);
+    }
+
+    #[test]
+    fn pagoda_bank_never_grows_along_a_jump() {
+        for grid in &invariants::PAGODA_BANK {
+            for &(from, jumped, to) in &MOVE_TABLE {
+                let weight = |pos: u8| invariants::bank_weight(grid, pos);
+                assert!(
+                    weight(to) <= weight(from) + weight(jumped),
+                    "jump {:?} in {:?}",
+                    (from, jumped, to),
+                    grid
+                );
+            }
+        }
+
+        for (i, &pos) in VALID_POSITIONS.iter().enumerate() {
+            let weight = invariants::bank_weight(&invariants::PAGODA_BANK[0], pos);
+            assert_eq!(weight, PAGODA_WEIGHTS[i] as i32);
+        }
+    }
+
+    #[test]
+    fn vertical_moves_stay_inside_the_cross() {
+        // Одна пустая клетка — все прыжки в неё, включая из боковых рукавов
+        for &hole in &VALID_POSITIONS {
+            let pegs = VALID_MASK ^ (1u64 << hole);
+            assert_eq!(sorted(generate_moves(pegs)), sorted(generate_moves_naive(pegs)));
+            // Заполненные клетки вне доски не должны давать ходов
+            let noisy = pegs | !VALID_MASK;
+            assert_eq!(sorted(generate_moves(noisy)), sorted(generate_moves_naive(noisy)));
+        }
+    }
+}
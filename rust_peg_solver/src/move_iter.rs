@@ -0,0 +1,77 @@
+/*
+ * rust_peg_solver/src/move_iter.rs
+ *
+ * Ленивая генерация ходов без выделения Vec.
+ * Маски направлений считаются один раз при создании итератора, дальше
+ * каждый ход — это младший установленный бит текущей маски.
+ */
+
+use pyo3::prelude::*;
+
+use crate::{direction_masks_on, popcount64, Jump, VALID_MASK};
+
+// Шаг прыжка для направлений: вправо, влево, вниз, вверх
+const STEPS: [i8; 4] = [1, -1, 7, -7];
+
+/// Итератор по ходам позиции
+#[pyclass]
+pub(crate) struct MoveIterator {
+    // Клетки, из которых ещё не выданы ходы, по направлениям STEPS
+    remaining: [u64; 4],
+    direction: usize,
+}
+
+impl MoveIterator {
+    pub(crate) fn new(pegs: u64) -> Self {
+        let (can_right, can_left, can_down, can_up) = direction_masks_on(pegs, VALID_MASK);
+
+        MoveIterator {
+            remaining: [can_right, can_left, can_down, can_up],
+            direction: 0,
+        }
+    }
+}
+
+impl Iterator for MoveIterator {
+    type Item = Jump;
+
+    fn next(&mut self) -> Option<Jump> {
+        while self.direction < STEPS.len() {
+            let bits = &mut self.remaining[self.direction];
+            if *bits == 0 {
+                self.direction += 1;
+                continue;
+            }
+            let pos = bits.trailing_zeros() as i8;
+            *bits &= *bits - 1;
+            let step = STEPS[self.direction];
+            return Some((pos as u8, (pos + step) as u8, (pos + 2 * step) as u8));
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left: u32 = self.remaining[self.direction.min(STEPS.len())..]
+            .iter()
+            .map(|&bits| popcount64(bits))
+            .sum();
+        (left as usize, Some(left as usize))
+    }
+}
+
+#[pymethods]
+impl MoveIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<Jump> {
+        slf.next()
+    }
+}
+
+/// Ленивый итератор по ходам (без выделения списка)
+#[pyfunction]
+pub(crate) fn rust_iter_moves(pegs: u64) -> PyResult<MoveIterator> {
+    Ok(MoveIterator::new(pegs))
+}
@@ -0,0 +1,67 @@
+/*
+ * rust_peg_solver/src/moves.rs
+ *
+ * Типизированный ход для Python: именованные поля вместо кортежа
+ * (откуда, через какую позицию, куда). Допустимы только прыжки из MOVE_TABLE.
+ */
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::peg_io::cell_name;
+use crate::{generate_moves, Jump, MOVE_TABLE};
+
+/// Ход английской доски
+#[pyclass(frozen, eq, hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Move {
+    #[pyo3(get)]
+    from_pos: u8,
+    #[pyo3(get)]
+    jumped: u8,
+    #[pyo3(get)]
+    to_pos: u8,
+}
+
+impl From<Jump> for Move {
+    fn from((from_pos, jumped, to_pos): Jump) -> Self {
+        Move { from_pos, jumped, to_pos }
+    }
+}
+
+#[pymethods]
+impl Move {
+    #[new]
+    fn new(from_pos: u8, jumped: u8, to_pos: u8) -> PyResult<Self> {
+        let mv = (from_pos, jumped, to_pos);
+        if !MOVE_TABLE.contains(&mv) {
+            return Err(PyValueError::new_err(format!("{:?} is not a jump on the board", mv)));
+        }
+        Ok(mv.into())
+    }
+
+    /// Направление прыжка: "right", "left", "down" или "up"
+    fn direction(&self) -> &'static str {
+        match self.to_pos as i16 - self.from_pos as i16 {
+            2 => "right",
+            -2 => "left",
+            d if d > 0 => "down",
+            _ => "up",
+        }
+    }
+
+    /// Тот же ход в виде кортежа (откуда, через, куда)
+    fn as_tuple(&self) -> Jump {
+        (self.from_pos, self.jumped, self.to_pos)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Move({}-{})", cell_name(self.from_pos), cell_name(self.to_pos))
+    }
+}
+
+/// Ходы позиции в виде объектов Move (порядок как в rust_get_moves)
+#[pyfunction]
+pub(crate) fn rust_get_moves_typed(pegs: u64) -> PyResult<Vec<Move>> {
+    Ok(generate_moves(pegs).into_iter().map(Move::from).collect())
+}
@@ -0,0 +1,354 @@
+/*
+ * rust_peg_solver/src/peg_io.rs
+ *
+ * Текстовое представление доски: ASCII сетка 7x7.
+ * 'o' — колышек, '.' — пустая валидная клетка, пробел — клетка вне доски.
+ * Нотация ходов: столбцы a–g (pos % 7), ряды 1–7 (pos / 7 + 1), например d2-d4.
+ * Компактный код позиции: 33 бита валидных клеток, упакованные в 5 байт
+ * и закодированные base64url без паддинга (7 символов).
+ * Битовая строка: 33 символа '1'/'0', i-й символ — клетка VALID_POSITIONS[i].
+ * Запись партии: битовая строка стартовой позиции, затем по ходу в строке.
+ * Внешняя нумерация: таблица из 33 номеров другой программы в порядке
+ * VALID_POSITIONS; по умолчанию — row-major индекс 0..48, совпадающий с нашим.
+ */
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::geometry::ENGLISH_GEOMETRY;
+use crate::{
+    apply_jump, generate_moves, is_valid_board, position_index, Jump, VALID_MASK, VALID_POSITIONS,
+};
+
+/// ASCII сетка 7x7 с переводами строк между рядами
+pub(crate) fn render(pegs: u64) -> String {
+    let grid = ENGLISH_GEOMETRY;
+    let mut rows = Vec::with_capacity(grid.height() as usize);
+    for r in 0..grid.height() {
+        let mut row = String::with_capacity(grid.width() as usize);
+        for c in 0..grid.width() {
+            let pos = grid.from_rc(r, c);
+            let ch = if (VALID_MASK >> pos) & 1 == 0 {
+                ' '
+            } else if (pegs >> pos) & 1 != 0 {
+                'o'
+            } else {
+                '.'
+            };
+            row.push(ch);
+        }
+        rows.push(row);
+    }
+    rows.join("\n")
+}
+
+/// Отрисовка доски для отладки
+#[pyfunction]
+pub(crate) fn rust_render(pegs: u64) -> PyResult<String> {
+    Ok(render(pegs))
+}
+
+/// Разбор ASCII сетки обратно в битовую доску
+#[pyfunction]
+pub(crate) fn rust_parse(board_str: &str) -> PyResult<u64> {
+    let grid = ENGLISH_GEOMETRY;
+    let body = board_str.strip_suffix('\n').unwrap_or(board_str);
+    let lines: Vec<&str> = body.split('\n').collect();
+    if lines.len() != grid.height() as usize {
+        return Err(PyValueError::new_err(format!(
+            "expected {} rows, got {}",
+            grid.height(),
+            lines.len()
+        )));
+    }
+
+    let mut pegs = 0u64;
+    for (r, line) in lines.iter().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.chars().count() > grid.width() as usize {
+            return Err(PyValueError::new_err(format!(
+                "row {} is longer than {} cells",
+                r,
+                grid.width()
+            )));
+        }
+        for (c, ch) in line.chars().enumerate() {
+            let pos = grid.from_rc(r as u8, c as u8);
+            match ch {
+                'o' => {
+                    if (VALID_MASK >> pos) & 1 == 0 {
+                        return Err(PyValueError::new_err(format!(
+                            "peg at row {}, col {} is outside the board",
+                            r, c
+                        )));
+                    }
+                    pegs |= 1u64 << pos;
+                }
+                '.' | ' ' => {}
+                other => {
+                    return Err(PyValueError::new_err(format!(
+                        "unexpected character {:?} at row {}, col {}",
+                        other, r, c
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(pegs)
+}
+
+/// Клетка в нотации: буква столбца и номер ряда
+pub(crate) fn cell_name(pos: u8) -> String {
+    let (r, c) = ENGLISH_GEOMETRY.to_rc(pos);
+    format!("{}{}", (b'a' + c) as char, r + 1)
+}
+
+/// Разбор клетки вида "d4" в позицию на доске
+fn parse_cell(cell: &str) -> PyResult<u8> {
+    let bytes = cell.as_bytes();
+    let well_formed = bytes.len() == 2
+        && (b'a'..=b'g').contains(&bytes[0])
+        && (b'1'..=b'7').contains(&bytes[1]);
+    if !well_formed {
+        return Err(PyValueError::new_err(format!("invalid cell {:?}", cell)));
+    }
+    let pos = ENGLISH_GEOMETRY.from_rc(bytes[1] - b'1', bytes[0] - b'a');
+    if (VALID_MASK >> pos) & 1 == 0 {
+        return Err(PyValueError::new_err(format!(
+            "cell {} is outside the board",
+            cell
+        )));
+    }
+    Ok(pos)
+}
+
+/// Ход в нотации "d2-d4"
+#[pyfunction]
+pub(crate) fn rust_move_notation(from_pos: u8, to_pos: u8) -> PyResult<String> {
+    for pos in [from_pos, to_pos] {
+        if pos >= ENGLISH_GEOMETRY.cells() {
+            return Err(PyValueError::new_err(format!(
+                "position {} is out of range",
+                pos
+            )));
+        }
+    }
+    Ok(format!("{}-{}", cell_name(from_pos), cell_name(to_pos)))
+}
+
+/// Разбор нотации "d2-d4" в полный ход (откуда, через какую позицию, куда)
+#[pyfunction]
+pub(crate) fn rust_parse_notation(s: &str) -> PyResult<Jump> {
+    let (from, to) = s
+        .trim()
+        .split_once('-')
+        .ok_or_else(|| PyValueError::new_err(format!("expected \"from-to\", got {:?}", s)))?;
+    let from_pos = parse_cell(from)?;
+    let to_pos = parse_cell(to)?;
+
+    let (fr, fc) = ENGLISH_GEOMETRY.to_rc(from_pos);
+    let (tr, tc) = ENGLISH_GEOMETRY.to_rc(to_pos);
+    let straight_jump = (fr == tr && fc.abs_diff(tc) == 2) || (fc == tc && fr.abs_diff(tr) == 2);
+    if !straight_jump {
+        return Err(PyValueError::new_err(format!(
+            "{} is not a jump over one cell",
+            s
+        )));
+    }
+
+    Ok((from_pos, (from_pos + to_pos) / 2, to_pos))
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// 5 байт = 40 бит, в base64 это 7 символов (последние 2 бита нулевые)
+const ENCODED_LEN: usize = 7;
+
+/// Позиция в компактный код из 7 символов base64url
+#[pyfunction]
+pub(crate) fn rust_to_base64(pegs: u64) -> PyResult<String> {
+    if !is_valid_board(pegs) {
+        return Err(PyValueError::new_err(format!(
+            "board has pegs outside the valid mask: {:#x}",
+            pegs & !VALID_MASK
+        )));
+    }
+
+    // i-я валидная клетка -> i-й бит упакованного значения
+    let mut packed = 0u64;
+    for (i, &pos) in VALID_POSITIONS.iter().enumerate() {
+        packed |= ((pegs >> pos) & 1) << i;
+    }
+
+    // 40 бит big-endian, дополненные до 42 нулями справа
+    let bits = packed << 2;
+    let encoded = (0..ENCODED_LEN)
+        .map(|i| {
+            let index = (bits >> (6 * (ENCODED_LEN - 1 - i))) & 0x3F;
+            BASE64_ALPHABET[index as usize] as char
+        })
+        .collect();
+    Ok(encoded)
+}
+
+/// Разбор кода из rust_to_base64 обратно в позицию
+#[pyfunction]
+pub(crate) fn rust_from_base64(s: &str) -> PyResult<u64> {
+    let code = s.trim_end_matches('=');
+    if code.len() != ENCODED_LEN {
+        return Err(PyValueError::new_err(format!(
+            "expected {} base64 characters, got {:?}",
+            ENCODED_LEN, s
+        )));
+    }
+
+    let mut bits = 0u64;
+    for ch in code.bytes() {
+        let index = BASE64_ALPHABET.iter().position(|&c| c == ch).ok_or_else(|| {
+            PyValueError::new_err(format!("invalid base64 character {:?}", ch as char))
+        })?;
+        bits = bits << 6 | index as u64;
+    }
+
+    let packed = bits >> 2;
+    if bits & 0b11 != 0 || packed >> VALID_POSITIONS.len() != 0 {
+        return Err(PyValueError::new_err(format!(
+            "code {:?} has bits beyond the {} valid cells",
+            s,
+            VALID_POSITIONS.len()
+        )));
+    }
+
+    let mut pegs = 0u64;
+    for (i, &pos) in VALID_POSITIONS.iter().enumerate() {
+        pegs |= ((packed >> i) & 1) << pos;
+    }
+    Ok(pegs)
+}
+
+/// 33 символа '1'/'0' в порядке VALID_POSITIONS (клетки вне доски не пишутся)
+#[pyfunction]
+pub(crate) fn rust_to_bitstring(pegs: u64) -> PyResult<String> {
+    Ok(VALID_POSITIONS
+        .iter()
+        .map(|&pos| if (pegs >> pos) & 1 != 0 { '1' } else { '0' })
+        .collect())
+}
+
+/// Разбор битовой строки из 33 символов '1'/'0' в порядке VALID_POSITIONS
+#[pyfunction]
+pub(crate) fn rust_from_bitstring(s: &str) -> PyResult<u64> {
+    let len = s.chars().count();
+    if len != VALID_POSITIONS.len() {
+        return Err(PyValueError::new_err(format!(
+            "expected {} characters, got {}",
+            VALID_POSITIONS.len(),
+            len
+        )));
+    }
+
+    let mut pegs = 0u64;
+    for (i, (ch, &pos)) in s.chars().zip(VALID_POSITIONS.iter()).enumerate() {
+        match ch {
+            '1' => pegs |= 1u64 << pos,
+            '0' => {}
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unexpected character {:?} at index {}",
+                    other, i
+                )));
+            }
+        }
+    }
+
+    Ok(pegs)
+}
+
+/// Запись партии: первая строка — стартовая позиция, дальше по ходу "d2-d4" в строке
+#[pyfunction]
+pub(crate) fn rust_serialize_game(start: u64, moves: Vec<Jump>) -> PyResult<String> {
+    let mut lines = vec![rust_to_bitstring(start)?];
+    for (from_pos, _, to_pos) in moves {
+        lines.push(rust_move_notation(from_pos, to_pos)?);
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Разбор записи партии с проверкой, что каждый ход допустим
+#[pyfunction]
+pub(crate) fn rust_deserialize_game(text: &str) -> PyResult<(u64, Vec<Jump>)> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let start = rust_from_bitstring(
+        lines
+            .next()
+            .ok_or_else(|| PyValueError::new_err("game record is empty"))?,
+    )?;
+
+    let mut pegs = start;
+    let mut moves = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let mv = rust_parse_notation(line)?;
+        if !generate_moves(pegs).contains(&mv) {
+            return Err(PyValueError::new_err(format!(
+                "move {} {} is not legal",
+                index, line
+            )));
+        }
+        pegs = apply_jump(pegs, mv);
+        moves.push(mv);
+    }
+
+    Ok((start, moves))
+}
+
+// Внешняя таблица нумерации: 33 различных номера, по одному на клетку
+fn external_table(table: Option<Vec<u8>>) -> PyResult<Vec<u8>> {
+    let table = table.unwrap_or_else(|| VALID_POSITIONS.to_vec());
+    if table.len() != VALID_POSITIONS.len() {
+        return Err(PyValueError::new_err(format!(
+            "external table must have {} entries, got {}",
+            VALID_POSITIONS.len(),
+            table.len()
+        )));
+    }
+    let mut seen = [false; 256];
+    for &id in &table {
+        if seen[id as usize] {
+            return Err(PyValueError::new_err(format!(
+                "external table repeats number {}",
+                id
+            )));
+        }
+        seen[id as usize] = true;
+    }
+    Ok(table)
+}
+
+/// Позиция этого модуля -> номер клетки во внешней нумерации
+#[pyfunction]
+#[pyo3(signature = (pos, table=None))]
+pub(crate) fn rust_to_external_index(pos: u8, table: Option<Vec<u8>>) -> PyResult<u8> {
+    let table = external_table(table)?;
+    let index = position_index(pos)
+        .ok_or_else(|| PyValueError::new_err(format!("position {} is not on the board", pos)))?;
+    Ok(table[index])
+}
+
+/// Номер клетки во внешней нумерации -> позиция этого модуля
+#[pyfunction]
+#[pyo3(signature = (external, table=None))]
+pub(crate) fn rust_from_external_index(external: u8, table: Option<Vec<u8>>) -> PyResult<u8> {
+    let table = external_table(table)?;
+    table
+        .iter()
+        .position(|&id| id == external)
+        .map(|index| VALID_POSITIONS[index])
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "external number {} is not in the table",
+                external
+            ))
+        })
+}
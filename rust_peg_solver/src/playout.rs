@@ -0,0 +1,176 @@
+/*
+ * rust_peg_solver/src/playout.rs
+ *
+ * Случайные партии для Monte Carlo оценок.
+ * Генератор xorshift64 с явным seed, поэтому партии воспроизводимы.
+ */
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::geometry::ENGLISH_GEOMETRY;
+use crate::{
+    apply_jump, count_moves_on, evaluate_position, generate_moves, popcount64, Jump, VALID_MASK,
+};
+
+// Замена нулевого seed: xorshift из нуля навсегда остаётся в нуле
+const ZERO_SEED_REPLACEMENT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+pub(crate) struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        let state = if seed == 0 { ZERO_SEED_REPLACEMENT } else { seed };
+        XorShift64 { state }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    // Индекс в диапазоне 0..n (n > 0)
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // Равномерное число в [0, 1) из старших 53 бит
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Случайные ходы до тупика: (финальная позиция, число сыгранных ходов)
+pub(crate) fn random_playout(start: u64, seed: u64) -> (u64, usize) {
+    let mut rng = XorShift64::new(seed);
+    let mut pegs = start;
+    let mut played = 0usize;
+
+    loop {
+        let moves = generate_moves(pegs);
+        if moves.is_empty() {
+            return (pegs, played);
+        }
+        pegs = apply_jump(pegs, moves[rng.below(moves.len())]);
+        played += 1;
+    }
+}
+
+/// Случайная партия с воспроизводимым seed
+#[pyfunction]
+pub(crate) fn rust_random_playout(pegs: u64, seed: u64) -> PyResult<(u64, usize)> {
+    Ok(random_playout(pegs, seed))
+}
+
+/// Полная случайная партия с центральной дырой: (стартовая позиция, ходы)
+///
+/// Ходы выбираются тем же генератором, что в random_playout, поэтому при
+/// одинаковом seed обе функции играют одну и ту же партию.
+pub(crate) fn random_game(seed: u64) -> (u64, Vec<Jump>) {
+    let (row, col) = ENGLISH_GEOMETRY.center();
+    let start = VALID_MASK & !(1u64 << ENGLISH_GEOMETRY.from_rc(row, col));
+    let mut rng = XorShift64::new(seed);
+    let mut pegs = start;
+    let mut history = Vec::new();
+
+    loop {
+        let moves = generate_moves(pegs);
+        if moves.is_empty() {
+            return (start, history);
+        }
+        let mv = moves[rng.below(moves.len())];
+        pegs = apply_jump(pegs, mv);
+        history.push(mv);
+    }
+}
+
+/// Случайная партия от стандартного старта для наборов данных
+#[pyfunction]
+pub(crate) fn rust_random_game(seed: u64) -> PyResult<(u64, Vec<Jump>)> {
+    Ok(random_game(seed))
+}
+
+/// Доля случайных партий (seed = base_seed + i), закончившихся одним колышком
+pub(crate) fn sample_solvable(start: u64, trials: usize, base_seed: u64) -> f64 {
+    let solved = (0..trials)
+        .into_par_iter()
+        .filter(|&i| {
+            let (end, _) = random_playout(start, base_seed.wrapping_add(i as u64));
+            popcount64(end) == 1
+        })
+        .count();
+    solved as f64 / trials as f64
+}
+
+/// Оценка «лёгкости» позиции параллельными случайными партиями
+#[pyfunction]
+pub(crate) fn rust_sample_solvable(
+    py: Python<'_>,
+    pegs: u64,
+    trials: usize,
+    base_seed: u64,
+) -> PyResult<f64> {
+    if trials == 0 {
+        return Err(PyValueError::new_err("trials must be positive"));
+    }
+    Ok(py.allow_threads(|| sample_solvable(pegs, trials, base_seed)))
+}
+
+/// Партия с выбором хода по softmax оценок потомков (меньше оценка — выше вероятность)
+pub(crate) fn guided_playout(start: u64, seed: u64, temperature: f64) -> (u64, usize) {
+    let mut rng = XorShift64::new(seed);
+    let mut pegs = start;
+    let mut played = 0usize;
+
+    loop {
+        let moves = generate_moves(pegs);
+        if moves.is_empty() {
+            return (pegs, played);
+        }
+        let scores: Vec<f64> = moves
+            .iter()
+            .map(|&mv| {
+                let child = apply_jump(pegs, mv);
+                evaluate_position(child, count_moves_on(child, VALID_MASK) as usize)
+            })
+            .collect();
+
+        // Сдвиг на минимум, чтобы exp не переполнялся при низкой температуре
+        let best = scores.iter().copied().fold(f64::INFINITY, f64::min);
+        let weights: Vec<f64> = scores
+            .iter()
+            .map(|&score| (-(score - best) / temperature).exp())
+            .collect();
+        let mut pick = rng.next_f64() * weights.iter().sum::<f64>();
+        let mut index = moves.len() - 1;
+        for (i, &weight) in weights.iter().enumerate() {
+            if pick < weight {
+                index = i;
+                break;
+            }
+            pick -= weight;
+        }
+
+        pegs = apply_jump(pegs, moves[index]);
+        played += 1;
+    }
+}
+
+/// Партия, смещённая к лучшим по оценке ходам; temperature > 0
+#[pyfunction]
+pub(crate) fn rust_guided_playout(pegs: u64, seed: u64, temperature: f64) -> PyResult<(u64, usize)> {
+    if !(temperature > 0.0 && temperature.is_finite()) {
+        return Err(PyValueError::new_err(format!(
+            "temperature must be positive and finite, got {}",
+            temperature
+        )));
+    }
+    Ok(guided_playout(pegs, seed, temperature))
+}
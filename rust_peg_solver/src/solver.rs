@@ -0,0 +1,957 @@
+/*
+ * rust_peg_solver/src/solver.rs
+ *
+ * Поиск решения целиком на стороне Rust.
+ * Итеративный DFS с явным стеком — без рекурсии и без риска
+ * переполнить нативный стек на глубоких досках.
+ */
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::invariants::{position_class, provably_unsolvable};
+use crate::symmetry::{all_symmetries, canonical, map_position, stabilizer};
+use crate::{
+    apply_jump, check_target, count_moves_on, evaluate_position, generate_moves,
+    generate_reverse_moves, is_dead, is_valid_board, pagoda_value, popcount64, target_unreachable,
+    Jump, VALID_MASK, VALID_POSITIONS,
+};
+
+/// Счётчики поиска: раскрытые позиции и встреченные тупики
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SearchStats {
+    pub(crate) nodes: u64,
+    pub(crate) dead_ends: u64,
+}
+
+/// Итеративный DFS: возвращает последовательность ходов до одного колышка
+///
+/// Чистая Rust функция без PyO3 — её же вызывают бенчмарки из benches/.
+pub fn solve_core(start: u64) -> Option<Vec<Jump>> {
+    solve_core_until(start, &AtomicBool::new(false), &mut SearchStats::default())
+}
+
+/// DFS, который прекращается, как только выставлен флаг `stop`
+fn solve_core_until(start: u64, stop: &AtomicBool, stats: &mut SearchStats) -> Option<Vec<Jump>> {
+    match popcount64(start) {
+        0 => return None,
+        1 => return Some(Vec::new()),
+        _ => {}
+    }
+
+    // Кадр стека: позиция, её ходы и индекс следующего хода для проверки
+    let mut stack: Vec<(u64, Vec<Jump>, usize)> = vec![(start, generate_moves(start), 0)];
+    let mut path: Vec<Jump> = Vec::new();
+    stats.nodes += 1;
+
+    while let Some((pegs, moves, next)) = stack.last_mut() {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        if *next >= moves.len() {
+            stack.pop();
+            path.pop();
+            continue;
+        }
+
+        let mv = moves[*next];
+        *next += 1;
+        let child = apply_jump(*pegs, mv);
+
+        if popcount64(child) == 1 {
+            path.push(mv);
+            return Some(path);
+        }
+        if is_dead(child) {
+            stats.dead_ends += 1;
+            continue;
+        }
+
+        path.push(mv);
+        stack.push((child, generate_moves(child), 0));
+        stats.nodes += 1;
+    }
+
+    None
+}
+
+/// Полное решение: список ходов до одного колышка или None
+#[pyfunction]
+pub(crate) fn rust_solve(pegs: u64) -> PyResult<Option<Vec<Jump>>> {
+    Ok(solve_core(pegs))
+}
+
+/// Позиции вдоль найденного решения, от стартовой до последнего колышка
+#[pyfunction]
+pub(crate) fn rust_solution_boards(pegs: u64) -> PyResult<Option<Vec<u64>>> {
+    Ok(solve_core(pegs).map(|moves| {
+        let mut boards = Vec::with_capacity(moves.len() + 1);
+        let mut current = pegs;
+        boards.push(current);
+        for mv in moves {
+            current = apply_jump(current, mv);
+            boards.push(current);
+        }
+        boards
+    }))
+}
+
+/// Жадная попытка: каждый раз ход к потомку с лучшей (наименьшей) оценкой
+///
+/// Без возвратов, поэтому решение не гарантировано; при равных оценках
+/// берётся первый ход в порядке generate_moves.
+pub(crate) fn solve_greedy(start: u64) -> Option<Vec<Jump>> {
+    let mut pegs = start;
+    let mut path = Vec::new();
+    while popcount64(pegs) > 1 {
+        let mut best: Option<(f64, Jump, u64)> = None;
+        for mv in generate_moves(pegs) {
+            let child = apply_jump(pegs, mv);
+            let score = evaluate_position(child, count_moves_on(child, VALID_MASK) as usize);
+            if best.is_none_or(|(best_score, _, _)| score < best_score) {
+                best = Some((score, mv, child));
+            }
+        }
+        let (_, mv, child) = best?;
+        path.push(mv);
+        pegs = child;
+    }
+    (pegs != 0).then_some(path)
+}
+
+/// Быстрая эвристическая попытка решения; None — жадный путь зашёл в тупик
+#[pyfunction]
+pub(crate) fn rust_solve_greedy(pegs: u64) -> PyResult<Option<Vec<Jump>>> {
+    Ok(solve_greedy(pegs))
+}
+
+/// Решение вместе со статистикой: (ходы, раскрытые позиции, тупики)
+#[pyfunction]
+pub(crate) fn rust_solve_stats(pegs: u64) -> PyResult<(Option<Vec<Jump>>, u64, u64)> {
+    let mut stats = SearchStats::default();
+    let solution = solve_core_until(pegs, &AtomicBool::new(false), &mut stats);
+    Ok((solution, stats.nodes, stats.dead_ends))
+}
+
+/// DFS до одного колышка именно на target_pos
+///
+/// Ветви, где pagoda функция цели доказывает её недостижимость, отсекаются.
+pub(crate) fn solve_to(start: u64, target_pos: u8) -> Option<Vec<Jump>> {
+    let goal = 1u64 << target_pos;
+    if start == goal {
+        return Some(Vec::new());
+    }
+    if popcount64(start) <= 1 || target_unreachable(start, target_pos) {
+        return None;
+    }
+
+    let mut stack: Vec<(u64, Vec<Jump>, usize)> = vec![(start, generate_moves(start), 0)];
+    let mut path: Vec<Jump> = Vec::new();
+
+    while let Some((pegs, moves, next)) = stack.last_mut() {
+        if *next >= moves.len() {
+            stack.pop();
+            path.pop();
+            continue;
+        }
+
+        let mv = moves[*next];
+        *next += 1;
+        let child = apply_jump(*pegs, mv);
+
+        if child == goal {
+            path.push(mv);
+            return Some(path);
+        }
+        if popcount64(child) == 1 || is_dead(child) || target_unreachable(child, target_pos) {
+            continue;
+        }
+
+        path.push(mv);
+        stack.push((child, generate_moves(child), 0));
+    }
+
+    None
+}
+
+/// Решение с финишем на заданной клетке (24 — центр английской доски)
+#[pyfunction]
+pub(crate) fn rust_solve_to(
+    py: Python<'_>,
+    pegs: u64,
+    target_pos: u8,
+) -> PyResult<Option<Vec<Jump>>> {
+    check_target(target_pos)?;
+    Ok(py.allow_threads(|| solve_to(pegs, target_pos)))
+}
+
+/// Можно ли закончить одним колышком на target_pos (DFS с таблицей тупиковых позиций)
+fn can_finish_at(start: u64, target_pos: u8) -> bool {
+    let goal = 1u64 << target_pos;
+    if start == goal {
+        return true;
+    }
+    if popcount64(start) <= 1 || target_unreachable(start, target_pos) {
+        return false;
+    }
+
+    let mut failed: HashSet<u64> = HashSet::new();
+    let mut stack: Vec<(u64, Vec<Jump>, usize)> = vec![(start, generate_moves(start), 0)];
+
+    while let Some((pegs, moves, next)) = stack.last_mut() {
+        if *next >= moves.len() {
+            failed.insert(*pegs);
+            stack.pop();
+            continue;
+        }
+
+        let mv = moves[*next];
+        *next += 1;
+        let child = apply_jump(*pegs, mv);
+
+        if child == goal {
+            return true;
+        }
+        if popcount64(child) == 1
+            || failed.contains(&child)
+            || is_dead(child)
+            || target_unreachable(child, target_pos)
+        {
+            continue;
+        }
+
+        stack.push((child, generate_moves(child), 0));
+    }
+
+    false
+}
+
+/// Клетки, на которых может остаться последний колышек
+///
+/// Клетки с другим классом раскраски отбрасываются сразу; для симметричных
+/// относительно стартовой позиции клеток поиск выполняется один раз.
+pub(crate) fn reachable_finishes(start: u64) -> Vec<u8> {
+    if popcount64(start) == 0 {
+        return Vec::new();
+    }
+    let class = position_class(start);
+    let symmetries = stabilizer(start);
+
+    let (mut found, mut checked) = (0u64, 0u64);
+    for &target in &VALID_POSITIONS {
+        if (checked >> target) & 1 != 0 {
+            continue;
+        }
+        let orbit = symmetries
+            .iter()
+            .fold(0u64, |acc, &index| acc | 1u64 << map_position(target, index));
+        checked |= orbit;
+        if position_class(1u64 << target) == class && can_finish_at(start, target) {
+            found |= orbit;
+        }
+    }
+
+    VALID_POSITIONS
+        .iter()
+        .copied()
+        .filter(|&pos| (found >> pos) & 1 != 0)
+        .collect()
+}
+
+/// Все клетки, где может закончиться решение, по возрастанию
+#[pyfunction]
+pub(crate) fn rust_reachable_finishes(py: Python<'_>, pegs: u64) -> PyResult<Vec<u8>> {
+    Ok(py.allow_threads(|| reachable_finishes(pegs)))
+}
+
+/// DFS с таблицей позиций, для которых уже доказано отсутствие решения
+///
+/// Таблица хранит canonical формы, поэтому симметричные поддеревья тоже
+/// отсекаются. Путь восстанавливается как в solve_core.
+pub(crate) fn solve_memoized(start: u64) -> Option<Vec<Jump>> {
+    solve_memoized_with(start, &mut HashSet::new())
+}
+
+/// solve_memoized с внешней таблицей: её можно переиспользовать между вызовами
+fn solve_memoized_with(start: u64, unsolvable: &mut HashSet<u64>) -> Option<Vec<Jump>> {
+    match popcount64(start) {
+        0 => return None,
+        1 => return Some(Vec::new()),
+        _ => {}
+    }
+    if unsolvable.contains(&canonical(start)) {
+        return None;
+    }
+
+    let mut stack: Vec<(u64, Vec<Jump>, usize)> = vec![(start, generate_moves(start), 0)];
+    let mut path: Vec<Jump> = Vec::new();
+
+    while let Some((pegs, moves, next)) = stack.last_mut() {
+        if *next >= moves.len() {
+            // Все ходы перебраны без успеха
+            unsolvable.insert(canonical(*pegs));
+            stack.pop();
+            path.pop();
+            continue;
+        }
+
+        let mv = moves[*next];
+        *next += 1;
+        let child = apply_jump(*pegs, mv);
+
+        if popcount64(child) == 1 {
+            path.push(mv);
+            return Some(path);
+        }
+        if is_dead(child) || unsolvable.contains(&canonical(child)) {
+            continue;
+        }
+
+        path.push(mv);
+        stack.push((child, generate_moves(child), 0));
+    }
+
+    None
+}
+
+/// Решение с мемоизацией нерешаемых позиций (по canonical форме)
+#[pyfunction]
+pub(crate) fn rust_solve_memoized(py: Python<'_>, pegs: u64) -> PyResult<Option<Vec<Jump>>> {
+    Ok(py.allow_threads(|| solve_memoized(pegs)))
+}
+
+/// Полный DFS: каждое найденное решение сразу передаётся в callback
+///
+/// Если callback бросает исключение, поиск останавливается и ошибка
+/// пробрасывается вызывающему.
+#[pyfunction]
+pub(crate) fn rust_solve_all(py: Python<'_>, pegs: u64, callback: PyObject) -> PyResult<()> {
+    match popcount64(pegs) {
+        0 => return Ok(()),
+        1 => {
+            callback.call1(py, (Vec::<Jump>::new(),))?;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let mut stack: Vec<(u64, Vec<Jump>, usize)> = vec![(pegs, generate_moves(pegs), 0)];
+    let mut path: Vec<Jump> = Vec::new();
+
+    while let Some((pegs, moves, next)) = stack.last_mut() {
+        if *next >= moves.len() {
+            stack.pop();
+            path.pop();
+            continue;
+        }
+
+        let mv = moves[*next];
+        *next += 1;
+        let child = apply_jump(*pegs, mv);
+
+        if popcount64(child) == 1 {
+            path.push(mv);
+            callback.call1(py, (path.clone(),))?;
+            path.pop();
+            continue;
+        }
+        if is_dead(child) {
+            continue;
+        }
+
+        path.push(mv);
+        stack.push((child, generate_moves(child), 0));
+    }
+
+    Ok(())
+}
+
+/// Параллельный DFS: поддеревья первого хода ищутся на разных потоках
+pub(crate) fn solve_parallel(start: u64) -> Option<Vec<Jump>> {
+    match popcount64(start) {
+        0 => return None,
+        1 => return Some(Vec::new()),
+        _ => {}
+    }
+
+    let stop = AtomicBool::new(false);
+    generate_moves(start).into_par_iter().find_map_any(|mv| {
+        let child = apply_jump(start, mv);
+        let mut path = solve_core_until(child, &stop, &mut SearchStats::default())?;
+        stop.store(true, Ordering::Relaxed);
+        path.insert(0, mv);
+        Some(path)
+    })
+}
+
+/// Параллельное решение (rayon) по ходам первого уровня
+#[pyfunction]
+pub(crate) fn rust_solve_parallel(py: Python<'_>, pegs: u64) -> PyResult<Option<Vec<Jump>>> {
+    Ok(py.allow_threads(|| solve_parallel(pegs)))
+}
+
+/// Число последовательностей ходов, оставляющих один колышек
+pub(crate) fn count_solutions(start: u64) -> u64 {
+    count_solution_paths(start, |pegs| pegs)
+}
+
+/// То же число решений, но память общая для симметричных позиций
+///
+/// Симметрия доски переводит решения в решения, поэтому у всех позиций
+/// одной орбиты число решений одинаково и хранится под canonical формой.
+pub(crate) fn count_canonical_solutions(start: u64) -> u64 {
+    count_solution_paths(start, canonical)
+}
+
+// Обход в глубину с явным стеком; счётчик позиции суммируется из детей
+// и запоминается под key(позиция), когда все её ходы разобраны
+fn count_solution_paths(start: u64, key: fn(u64) -> u64) -> u64 {
+    match popcount64(start) {
+        0 => return 0,
+        1 => return 1,
+        _ => {}
+    }
+
+    let mut memo: HashMap<u64, u64> = HashMap::new();
+    let mut stack: Vec<(u64, Vec<Jump>, usize, u64)> =
+        vec![(start, generate_moves(start), 0, 0)];
+
+    while let Some((pegs, moves, next, total)) = stack.last_mut() {
+        if *next >= moves.len() {
+            let (pegs, total) = (*pegs, *total);
+            stack.pop();
+            memo.insert(key(pegs), total);
+            match stack.last_mut() {
+                Some(parent) => parent.3 += total,
+                None => return total,
+            }
+            continue;
+        }
+
+        let child = apply_jump(*pegs, moves[*next]);
+        *next += 1;
+        if popcount64(child) == 1 {
+            *total += 1;
+        } else if let Some(&known) = memo.get(&key(child)) {
+            *total += known;
+        } else if !is_dead(child) {
+            stack.push((child, generate_moves(child), 0, 0));
+        }
+    }
+
+    0
+}
+
+/// Подсчёт решений; use_symmetry не меняет ответ, только экономит память и время
+#[pyfunction]
+#[pyo3(signature = (pegs, use_symmetry = false))]
+pub(crate) fn rust_count_solutions(
+    py: Python<'_>,
+    pegs: u64,
+    use_symmetry: bool,
+) -> PyResult<u64> {
+    if use_symmetry {
+        Ok(py.allow_threads(|| count_canonical_solutions(pegs)))
+    } else {
+        Ok(py.allow_threads(|| count_solutions(pegs)))
+    }
+}
+
+// Больше 10 колышков таблица растёт слишком быстро для множества Python
+const MAX_TABLEBASE_PEGS: u32 = 10;
+
+/// Ретроградный анализ: canonical формы всех решаемых позиций с <= max_pegs колышков
+///
+/// Слой k + 1 получается обратными ходами из слоя k, начиная с одиночных колышков,
+/// поэтому в таблицу попадают ровно те позиции, из которых можно дойти до одного.
+pub(crate) fn build_tablebase(max_pegs: u32) -> HashSet<u64> {
+    let mut layer: HashSet<u64> = VALID_POSITIONS
+        .iter()
+        .map(|&pos| canonical(1u64 << pos))
+        .collect();
+    let mut solvable = layer.clone();
+
+    for _ in 1..max_pegs {
+        let mut next = HashSet::new();
+        for &pegs in &layer {
+            for mv in generate_reverse_moves(pegs) {
+                let parent = canonical(apply_jump(pegs, mv));
+                if !solvable.contains(&parent) {
+                    next.insert(parent);
+                }
+            }
+        }
+        solvable.extend(next.iter().copied());
+        layer = next;
+    }
+
+    solvable
+}
+
+/// Таблица эндшпиля: множество canonical форм решаемых позиций с <= max_pegs колышков
+///
+/// Позиция с <= max_pegs колышков решаема, если её canonical форма есть в множестве.
+/// Вместо словаря {позиция: bool} возвращается множество: значения False
+/// означали бы хранить все нерешаемые позиции (около 18 млн при max_pegs = 10).
+#[pyfunction]
+pub(crate) fn rust_build_tablebase(py: Python<'_>, max_pegs: u32) -> PyResult<HashSet<u64>> {
+    if max_pegs == 0 || max_pegs > MAX_TABLEBASE_PEGS {
+        return Err(PyValueError::new_err(format!(
+            "max_pegs must be between 1 and {}, got {}",
+            MAX_TABLEBASE_PEGS, max_pegs
+        )));
+    }
+    Ok(py.allow_threads(|| build_tablebase(max_pegs)))
+}
+
+/// Все позиции, достижимые не более чем за depth ходов (включая саму start)
+pub(crate) fn reachable_within(start: u64, depth: u32) -> Vec<u64> {
+    let mut seen: HashSet<u64> = HashSet::from([start]);
+    let mut frontier = vec![start];
+
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for &pegs in &frontier {
+            for mv in generate_moves(pegs) {
+                let child = apply_jump(pegs, mv);
+                if seen.insert(child) {
+                    next.push(child);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    let mut boards: Vec<u64> = seen.into_iter().collect();
+    boards.sort_unstable();
+    boards
+}
+
+/// Достижима ли target из start не более чем за max_depth ходов
+///
+/// Каждый ход снимает ровно один колышек, поэтому глубина известна заранее.
+/// Ветви отсекаются по классу раскраски и по pagoda функции.
+pub(crate) fn can_reach(start: u64, target: u64, max_depth: u32) -> bool {
+    let (start_count, target_count) = (popcount64(start), popcount64(target));
+    if target_count > start_count || start_count - target_count > max_depth {
+        return false;
+    }
+    if position_class(start) != position_class(target) {
+        return false;
+    }
+
+    let target_pagoda = pagoda_value(target);
+    let mut seen: HashSet<u64> = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(pegs) = stack.pop() {
+        if pegs == target {
+            return true;
+        }
+        if popcount64(pegs) == target_count {
+            continue;
+        }
+        for mv in generate_moves(pegs) {
+            let child = apply_jump(pegs, mv);
+            if pagoda_value(child) >= target_pagoda && seen.insert(child) {
+                stack.push(child);
+            }
+        }
+    }
+    false
+}
+
+/// Достижимость промежуточной позиции за ограниченное число ходов
+#[pyfunction]
+pub(crate) fn rust_can_reach(start: u64, target: u64, max_depth: u32) -> PyResult<bool> {
+    Ok(can_reach(start, target, max_depth))
+}
+
+/// BFS на depth ходов: отсортированный список различных позиций
+#[pyfunction]
+pub(crate) fn rust_reachable_within(pegs: u64, depth: u32) -> PyResult<Vec<u64>> {
+    Ok(reachable_within(pegs, depth))
+}
+
+/// Число различных позиций, достижимых из start (включая её саму)
+///
+/// Каждый ход снимает один колышек, поэтому слои BFS не пересекаются и в памяти
+/// достаточно держать два соседних слоя. С use_symmetry считаются canonical формы.
+pub(crate) fn count_reachable(start: u64, use_symmetry: bool) -> u64 {
+    let key = |pegs: u64| if use_symmetry { canonical(pegs) } else { pegs };
+    let mut layer: HashSet<u64> = HashSet::from([key(start)]);
+    let mut total = 0u64;
+
+    while !layer.is_empty() {
+        total += layer.len() as u64;
+        let mut next = HashSet::new();
+        for &pegs in &layer {
+            for mv in generate_moves(pegs) {
+                next.insert(key(apply_jump(pegs, mv)));
+            }
+        }
+        layer = next;
+    }
+
+    total
+}
+
+/// Точное число достижимых позиций (полный BFS по слоям)
+#[pyfunction]
+#[pyo3(signature = (pegs, use_symmetry=false))]
+pub(crate) fn rust_count_reachable(py: Python<'_>, pegs: u64, use_symmetry: bool) -> PyResult<u64> {
+    Ok(py.allow_threads(|| count_reachable(pegs, use_symmetry)))
+}
+
+/// Perft: число последовательностей ровно из depth ходов (без склейки позиций)
+pub(crate) fn perft(start: u64, depth: u32) -> u64 {
+    let mut leaves = 0u64;
+    let mut stack: Vec<(u64, u32)> = vec![(start, depth)];
+
+    while let Some((pegs, remaining)) = stack.pop() {
+        if remaining == 0 {
+            leaves += 1;
+            continue;
+        }
+        for mv in generate_moves(pegs) {
+            stack.push((apply_jump(pegs, mv), remaining - 1));
+        }
+    }
+
+    leaves
+}
+
+/// Perft для бенчмарков генерации и применения ходов
+#[pyfunction]
+pub(crate) fn rust_perft(py: Python<'_>, pegs: u64, depth: u32) -> PyResult<u64> {
+    Ok(py.allow_threads(|| perft(pegs, depth)))
+}
+
+/// Число партий, закончившихся без ходов, по числу оставшихся колышков
+///
+/// Каждый ход снимает один колышек, поэтому число путей до позиций считается
+/// по слоям. Симметричные позиции склеиваются через canonical: из любой позиции
+/// орбиты одинаково много ходов ведёт в каждую орбиту-потомка. С центрального
+/// старта партий около 5.8e20 — больше u64::MAX, поэтому счёт в u128.
+pub(crate) fn terminal_census(start: u64) -> BTreeMap<u32, u128> {
+    let mut census = BTreeMap::new();
+    let mut layer: HashMap<u64, u128> = HashMap::from([(canonical(start), 1)]);
+
+    while !layer.is_empty() {
+        let mut next: HashMap<u64, u128> = HashMap::new();
+        for (&pegs, &paths) in &layer {
+            let moves = generate_moves(pegs);
+            if moves.is_empty() {
+                *census.entry(popcount64(pegs)).or_insert(0) += paths;
+                continue;
+            }
+            for mv in moves {
+                *next.entry(canonical(apply_jump(pegs, mv))).or_insert(0) += paths;
+            }
+        }
+        layer = next;
+    }
+
+    census
+}
+
+/// Гистограмма длин партий: (число сыгранных ходов, сколько партий на этом остановилось)
+///
+/// Каждый ход снимает ровно один колышек, поэтому любое решение из N колышков
+/// имеет длину ровно N - 1: запись с этой глубиной — число решений, все
+/// остальные записи — тупики на меньшей глубине.
+#[pyfunction]
+pub(crate) fn rust_solution_length_histogram(
+    py: Python<'_>,
+    pegs: u64,
+) -> PyResult<Vec<(u32, u128)>> {
+    let total = popcount64(pegs);
+    let mut histogram: Vec<(u32, u128)> = py
+        .allow_threads(|| terminal_census(pegs))
+        .into_iter()
+        .map(|(remaining, count)| (total - remaining, count))
+        .collect();
+    histogram.sort_unstable();
+    Ok(histogram)
+}
+
+/// Тупики (is_dead) по числу оставшихся колышков: (колышки, сколько партий в них)
+///
+/// Считается по той же послойной переписи, что и гистограмма длин партий.
+pub(crate) fn deadend_census(start: u64) -> Vec<(u32, u128)> {
+    terminal_census(start)
+        .into_iter()
+        .filter(|&(remaining, _)| remaining > 1)
+        .collect()
+}
+
+/// Тупики (is_dead) по числу оставшихся колышков: (колышки, сколько тупиков)
+///
+/// Финалы с одним колышком тупиками не считаются, их число даёт rust_count_solutions.
+#[pyfunction]
+pub(crate) fn rust_deadend_census(py: Python<'_>, pegs: u64) -> PyResult<Vec<(u32, u128)>> {
+    Ok(py.allow_threads(|| deadend_census(pegs)))
+}
+
+/// DFS до limit различных (по canonical форме) тупиков с более чем одним колышком
+///
+/// Возвращаются canonical формы в порядке обнаружения; каждая позиция
+/// раскрывается один раз.
+pub(crate) fn collect_deadends(start: u64, limit: usize) -> Vec<u64> {
+    let mut deadends = Vec::new();
+    let mut visited: HashSet<u64> = HashSet::from([canonical(start)]);
+    let mut stack = vec![start];
+
+    while let Some(pegs) = stack.pop() {
+        if deadends.len() >= limit {
+            break;
+        }
+        if is_dead(pegs) {
+            deadends.push(canonical(pegs));
+            continue;
+        }
+        for mv in generate_moves(pegs) {
+            let child = apply_jump(pegs, mv);
+            if visited.insert(canonical(child)) {
+                stack.push(child);
+            }
+        }
+    }
+
+    deadends
+}
+
+/// Набор тупиковых позиций для обучения классификатора
+#[pyfunction]
+pub(crate) fn rust_collect_deadends(py: Python<'_>, pegs: u64, limit: usize) -> PyResult<Vec<u64>> {
+    Ok(py.allow_threads(|| collect_deadends(pegs, limit)))
+}
+
+// Узел двунаправленного поиска: представитель класса и (ключ родителя, ход из родителя)
+type SearchNode = (u64, Option<(u64, Jump)>);
+
+// Раскрывает один слой, возвращает ключи новых позиций
+fn expand_layer(
+    frontier: &[u64],
+    seen: &mut HashMap<u64, SearchNode>,
+    moves_of: fn(u64) -> Vec<Jump>,
+    key_of: impl Fn(u64) -> u64,
+) -> Vec<u64> {
+    let mut next = Vec::new();
+    for &key in frontier {
+        let pegs = seen[&key].0;
+        for mv in moves_of(pegs) {
+            let child = apply_jump(pegs, mv);
+            let child_key = key_of(child);
+            if let Entry::Vacant(slot) = seen.entry(child_key) {
+                slot.insert((child, Some((key, mv))));
+                next.push(child_key);
+            }
+        }
+    }
+    next
+}
+
+// Ходы от корня поиска до узла key (в порядке обхода от узла к корню)
+fn trace_moves(seen: &HashMap<u64, SearchNode>, key: u64) -> Vec<Jump> {
+    let mut moves = Vec::new();
+    let mut current = key;
+    while let Some(&(_, Some((parent, mv)))) = seen.get(&current) {
+        moves.push(mv);
+        current = parent;
+    }
+    moves
+}
+
+/// Двунаправленный поиск: вперёд от start, назад от goal, встреча посередине
+///
+/// Слои раскрываются по очереди (меньший первым), пока число колышков на
+/// фронтах не сравняется. Позиции склеиваются по canonical, только если goal
+/// переходит сама в себя при всех симметриях (например, один колышек в центре),
+/// иначе ключом служит сама позиция.
+pub(crate) fn solve_bidirectional(start: u64, goal: u64) -> Option<Vec<Jump>> {
+    if !is_valid_board(start) || !is_valid_board(goal) {
+        return None;
+    }
+    if goal == 0 || popcount64(goal) > popcount64(start) {
+        return None;
+    }
+
+    let symmetric_goal = all_symmetries(goal).iter().all(|&g| g == goal);
+    let key_of = |pegs: u64| if symmetric_goal { canonical(pegs) } else { pegs };
+
+    let mut forward: HashMap<u64, SearchNode> = HashMap::from([(key_of(start), (start, None))]);
+    let mut backward: HashMap<u64, SearchNode> = HashMap::from([(key_of(goal), (goal, None))]);
+    let mut forward_frontier = vec![key_of(start)];
+    let mut backward_frontier = vec![key_of(goal)];
+    let mut forward_pegs = popcount64(start);
+    let mut backward_pegs = popcount64(goal);
+
+    while forward_pegs > backward_pegs {
+        if forward_frontier.is_empty() || backward_frontier.is_empty() {
+            return None;
+        }
+        if forward_frontier.len() <= backward_frontier.len() {
+            forward_frontier = expand_layer(&forward_frontier, &mut forward, generate_moves, key_of);
+            forward_pegs -= 1;
+        } else {
+            backward_frontier =
+                expand_layer(&backward_frontier, &mut backward, generate_reverse_moves, key_of);
+            backward_pegs += 1;
+        }
+    }
+
+    for key in forward_frontier {
+        let Some(&(meet_backward, _)) = backward.get(&key) else {
+            continue;
+        };
+        let meet_forward = forward[&key].0;
+
+        // Симметрия, переводящая встречу с обратной стороны в прямую; goal она не меняет
+        let sym = all_symmetries(meet_backward).iter().position(|&p| p == meet_forward)?;
+
+        let mut path = trace_moves(&forward, key);
+        path.reverse();
+        path.extend(trace_moves(&backward, key).into_iter().map(|(from, jumped, to)| {
+            (map_position(from, sym), map_position(jumped, sym), map_position(to, sym))
+        }));
+        return Some(path);
+    }
+
+    None
+}
+
+/// Двунаправленный поиск от start до goal (обычно один колышек в центре)
+#[pyfunction]
+pub(crate) fn rust_solve_bidirectional(start: u64, goal: u64) -> PyResult<Option<Vec<Jump>>> {
+    Ok(solve_bidirectional(start, goal))
+}
+
+/// Решения для списка позиций, каждая на своём потоке rayon (порядок сохраняется)
+#[pyfunction]
+pub(crate) fn rust_solve_batch(
+    py: Python<'_>,
+    pegs_list: Vec<u64>,
+) -> PyResult<Vec<Option<Vec<Jump>>>> {
+    Ok(py.allow_threads(|| pegs_list.par_iter().map(|&pegs| solve_core(pegs)).collect()))
+}
+
+/// Только ответ «решаема ли» для каждой позиции — тот же поиск, что rust_solve_batch
+#[pyfunction]
+pub(crate) fn rust_solvable_batch(py: Python<'_>, pegs_list: Vec<u64>) -> PyResult<Vec<bool>> {
+    Ok(py.allow_threads(|| {
+        pegs_list
+            .par_iter()
+            .map(|&pegs| solve_core(pegs).is_some())
+            .collect()
+    }))
+}
+
+/// Сколько последних ходов истории нужно отменить, чтобы позиция снова решалась
+///
+/// None — решаемой позиции нет даже в начале истории. Таблица нерешаемых
+/// позиций общая для всех проверок: поздние позиции лежат в поддеревьях ранних.
+pub(crate) fn undo_to_solvable(pegs: u64, history: &[Jump]) -> Option<usize> {
+    let mut unsolvable: HashSet<u64> = HashSet::new();
+    let mut current = pegs;
+    for (undone, &mv) in history.iter().rev().enumerate() {
+        if solve_memoized_with(current, &mut unsolvable).is_some() {
+            return Some(undone);
+        }
+        current = apply_jump(current, mv);
+    }
+    solve_memoized_with(current, &mut unsolvable)
+        .is_some()
+        .then_some(history.len())
+}
+
+/// Подсказка «отмените N ходов»: ближайшая решаемая позиция в истории
+#[pyfunction]
+pub(crate) fn rust_undo_to_solvable(pegs: u64, history: Vec<Jump>) -> PyResult<usize> {
+    // Сначала проверяем, что история действительно привела к pegs
+    let mut current = pegs;
+    for &(from_pos, jumped, to_pos) in history.iter().rev() {
+        let played = from_pos < 64
+            && jumped < 64
+            && to_pos < 64
+            && (current >> to_pos) & 1 != 0
+            && (current >> from_pos) & 1 == 0
+            && (current >> jumped) & 1 == 0;
+        if !played {
+            return Err(PyValueError::new_err(format!(
+                "move {:?} cannot be undone from position {:#x}",
+                (from_pos, jumped, to_pos),
+                current
+            )));
+        }
+        current = apply_jump(current, (from_pos, jumped, to_pos));
+    }
+
+    undo_to_solvable(pegs, &history)
+        .ok_or_else(|| PyValueError::new_err("no position in the history is solvable"))
+}
+
+/// Поиск с лимитом узлов и отсечением по инвариантам
+///
+/// Каждый ход снимает ровно один колышек, поэтому оценка IDA* g + (колышки - 1)
+/// постоянна вдоль пути и углубление вырождается в одну итерацию — остаётся
+/// DFS. Ветвь отбрасывается, только если недостижима любая финальная клетка.
+pub(crate) fn solve_ida(start: u64, max_nodes: usize) -> Option<Vec<Jump>> {
+    if provably_unsolvable(start) {
+        return None;
+    }
+    if popcount64(start) == 1 {
+        return Some(Vec::new());
+    }
+
+    let mut expanded = 1usize;
+    let mut stack: Vec<(u64, Vec<Jump>, usize)> = vec![(start, generate_moves(start), 0)];
+    let mut path: Vec<Jump> = Vec::new();
+
+    while let Some((pegs, moves, next)) = stack.last_mut() {
+        if *next >= moves.len() {
+            stack.pop();
+            path.pop();
+            continue;
+        }
+
+        let mv = moves[*next];
+        *next += 1;
+        let child = apply_jump(*pegs, mv);
+
+        if popcount64(child) == 1 {
+            path.push(mv);
+            return Some(path);
+        }
+        if provably_unsolvable(child) {
+            continue;
+        }
+
+        if expanded >= max_nodes {
+            return None;
+        }
+        expanded += 1;
+
+        path.push(mv);
+        stack.push((child, generate_moves(child), 0));
+    }
+
+    None
+}
+
+/// Поиск с отсечением по инвариантам; None — лимит узлов исчерпан или решения нет
+#[pyfunction]
+pub(crate) fn rust_solve_ida(
+    py: Python<'_>,
+    pegs: u64,
+    max_nodes: usize,
+) -> PyResult<Option<Vec<Jump>>> {
+    Ok(py.allow_threads(|| solve_ida(pegs, max_nodes)))
+}
@@ -0,0 +1,201 @@
+/*
+ * rust_peg_solver/src/symmetry.rs
+ *
+ * Симметрии английской доски: 4 поворота и 4 отражения (группа D4).
+ * Каноническая форма позволяет схлопнуть 8 эквивалентных позиций в одну
+ * запись таблицы транспозиций.
+ */
+
+use std::collections::HashSet;
+
+use pyo3::prelude::*;
+
+use crate::{generate_moves, Jump, VALID_MASK};
+
+const GRID: usize = 7;
+
+// Перестановка клеток сетки 7x7 при повороте на 90°: (r, c) -> (c, 6 - r)
+const ROTATE_TABLE: [u8; 49] = build_rotate_table();
+
+// Перестановка при горизонтальном отражении: (r, c) -> (r, 6 - c)
+const MIRROR_TABLE: [u8; 49] = build_mirror_table();
+
+// Перестановка при отражении относительно главной диагонали: (r, c) -> (c, r)
+const DIAGONAL_TABLE: [u8; 49] = build_diagonal_table();
+
+const fn build_rotate_table() -> [u8; 49] {
+    let mut table = [0u8; 49];
+    let mut pos = 0;
+    while pos < GRID * GRID {
+        let (r, c) = (pos / GRID, pos % GRID);
+        table[pos] = (c * GRID + (GRID - 1 - r)) as u8;
+        pos += 1;
+    }
+    table
+}
+
+const fn build_mirror_table() -> [u8; 49] {
+    let mut table = [0u8; 49];
+    let mut pos = 0;
+    while pos < GRID * GRID {
+        let (r, c) = (pos / GRID, pos % GRID);
+        table[pos] = (r * GRID + (GRID - 1 - c)) as u8;
+        pos += 1;
+    }
+    table
+}
+
+const fn build_diagonal_table() -> [u8; 49] {
+    let mut table = [0u8; 49];
+    let mut pos = 0;
+    while pos < GRID * GRID {
+        let (r, c) = (pos / GRID, pos % GRID);
+        table[pos] = (c * GRID + r) as u8;
+        pos += 1;
+    }
+    table
+}
+
+// Применяет перестановку клеток к валидной части доски
+fn permute(pegs: u64, table: &[u8; 49]) -> u64 {
+    let mut result = 0u64;
+    let mut rest = pegs & VALID_MASK;
+    while rest != 0 {
+        let pos = rest.trailing_zeros() as usize;
+        result |= 1u64 << table[pos];
+        rest &= rest - 1;
+    }
+    result
+}
+
+pub(crate) fn rotate90(pegs: u64) -> u64 {
+    permute(pegs, &ROTATE_TABLE)
+}
+
+pub(crate) fn mirror_horizontal(pegs: u64) -> u64 {
+    permute(pegs, &MIRROR_TABLE)
+}
+
+pub(crate) fn mirror_diagonal(pegs: u64) -> u64 {
+    permute(pegs, &DIAGONAL_TABLE)
+}
+
+/// Поворот на 90° по часовой стрелке
+#[pyfunction]
+pub(crate) fn rust_rotate90(pegs: u64) -> PyResult<u64> {
+    Ok(rotate90(pegs))
+}
+
+/// Отражение слева направо
+#[pyfunction]
+pub(crate) fn rust_mirror_horizontal(pegs: u64) -> PyResult<u64> {
+    Ok(mirror_horizontal(pegs))
+}
+
+/// Отражение относительно главной диагонали (транспонирование сетки)
+#[pyfunction]
+pub(crate) fn rust_mirror_diagonal(pegs: u64) -> PyResult<u64> {
+    Ok(mirror_diagonal(pegs))
+}
+
+/// Все 8 образов позиции: повороты 0/90/180/270 и их отражения
+pub(crate) fn all_symmetries(pegs: u64) -> [u64; 8] {
+    let mut result = [0u64; 8];
+    let mut current = pegs & VALID_MASK;
+    for i in 0..4 {
+        result[2 * i] = current;
+        result[2 * i + 1] = mirror_horizontal(current);
+        current = rotate90(current);
+    }
+    result
+}
+
+/// Образ клетки при i-й симметрии (в порядке all_symmetries)
+pub(crate) fn map_position(pos: u8, index: usize) -> u8 {
+    let mut pos = pos;
+    for _ in 0..index / 2 {
+        pos = ROTATE_TABLE[pos as usize];
+    }
+    if index % 2 == 1 {
+        pos = MIRROR_TABLE[pos as usize];
+    }
+    pos
+}
+
+/// Минимальный из 8 симметричных образов
+pub(crate) fn canonical(pegs: u64) -> u64 {
+    all_symmetries(pegs).into_iter().min().unwrap_or(pegs)
+}
+
+/// Каноническая форма позиции относительно 8 симметрий доски
+#[pyfunction]
+pub(crate) fn rust_canonical(pegs: u64) -> PyResult<u64> {
+    Ok(canonical(pegs))
+}
+
+/// Batch каноническая форма для списка позиций
+#[pyfunction]
+pub(crate) fn rust_canonical_batch(pegs_list: Vec<u64>) -> PyResult<Vec<u64>> {
+    Ok(pegs_list.into_iter().map(canonical).collect())
+}
+
+/// Различные канонические формы в порядке первого появления
+#[pyfunction]
+pub(crate) fn rust_dedup_canonical(pegs_list: Vec<u64>) -> PyResult<Vec<u64>> {
+    let mut seen = HashSet::with_capacity(pegs_list.len());
+    Ok(pegs_list
+        .into_iter()
+        .map(canonical)
+        .filter(|&form| seen.insert(form))
+        .collect())
+}
+
+/// Все 8 симметричных вариантов позиции
+#[pyfunction]
+pub(crate) fn rust_all_symmetries(pegs: u64) -> PyResult<Vec<u64>> {
+    Ok(all_symmetries(pegs).to_vec())
+}
+
+/// Индексы симметрий (в порядке all_symmetries), оставляющих позицию на месте
+pub(crate) fn stabilizer(pegs: u64) -> Vec<usize> {
+    let pegs = pegs & VALID_MASK;
+    all_symmetries(pegs)
+        .iter()
+        .enumerate()
+        .filter(|&(_, &image)| image == pegs)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Размер орбиты позиции: 8 / |стабилизатор| (1, 2, 4 или 8)
+#[pyfunction]
+pub(crate) fn rust_orbit_size(pegs: u64) -> PyResult<u32> {
+    Ok(8 / stabilizer(pegs).len() as u32)
+}
+
+/// Легальные ходы по одному на орбиту стабилизатора позиции
+pub(crate) fn symmetry_reduced_moves(pegs: u64) -> Vec<Jump> {
+    let symmetries = stabilizer(pegs);
+    let mut kept: Vec<Jump> = Vec::new();
+    for mv in generate_moves(pegs) {
+        let (from, jumped, to) = mv;
+        let duplicate = symmetries.iter().any(|&sym| {
+            let image = (
+                map_position(from, sym),
+                map_position(jumped, sym),
+                map_position(to, sym),
+            );
+            kept.contains(&image)
+        });
+        if !duplicate {
+            kept.push(mv);
+        }
+    }
+    kept
+}
+
+/// Ходы без повторов, эквивалентных относительно симметрий текущей позиции
+#[pyfunction]
+pub(crate) fn rust_symmetry_reduced_moves(pegs: u64) -> PyResult<Vec<Jump>> {
+    Ok(symmetry_reduced_moves(pegs))
+}
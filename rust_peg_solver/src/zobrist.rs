@@ -0,0 +1,75 @@
+/*
+ * rust_peg_solver/src/zobrist.rs
+ *
+ * Zobrist хеширование для таблиц транспозиций.
+ * Таблица ключей генерируется один раз при загрузке модуля из фиксированного
+ * seed, поэтому хеши совпадают между запусками и их можно хранить на диске.
+ */
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+
+use crate::Jump;
+
+const ZOBRIST_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// Отдельный seed для ключей ходов, чтобы они не совпадали с ключами клеток
+const MOVE_SEED: u64 = 0xD6E8_FEB8_6659_FD93;
+
+// Ключ для каждого бита доски (используются только валидные позиции)
+static ZOBRIST_KEYS: Lazy<[u64; 64]> = Lazy::new(|| {
+    let mut state = ZOBRIST_SEED;
+    let mut keys = [0u64; 64];
+    for key in keys.iter_mut() {
+        *key = splitmix64(&mut state);
+    }
+    keys
+});
+
+// SplitMix64 — простой детерминированный генератор
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// XOR ключей всех занятых позиций
+pub(crate) fn zobrist_hash(pegs: u64) -> u64 {
+    let keys = &*ZOBRIST_KEYS;
+    let mut hash = 0u64;
+    let mut rest = pegs;
+    while rest != 0 {
+        let pos = rest.trailing_zeros() as usize;
+        hash ^= keys[pos];
+        rest &= rest - 1;
+    }
+    hash
+}
+
+/// Zobrist хеш позиции (детерминирован между запусками)
+#[pyfunction]
+pub(crate) fn rust_zobrist_hash(pegs: u64) -> PyResult<u64> {
+    Ok(zobrist_hash(pegs))
+}
+
+/// Ключ пары (позиция, последний ход): без хода совпадает с zobrist_hash
+pub(crate) fn state_key(pegs: u64, last_move: Option<Jump>) -> u64 {
+    let hash = zobrist_hash(pegs);
+    match last_move {
+        None => hash,
+        Some((from_pos, jumped, to_pos)) => {
+            let mut state =
+                MOVE_SEED ^ ((from_pos as u64) << 16 | (jumped as u64) << 8 | to_pos as u64);
+            hash ^ splitmix64(&mut state)
+        }
+    }
+}
+
+/// Один u64 ключ для кеша по (позиция, последний ход)
+#[pyfunction]
+#[pyo3(signature = (pegs, last_move=None))]
+pub(crate) fn rust_state_key(pegs: u64, last_move: Option<Jump>) -> PyResult<u64> {
+    Ok(state_key(pegs, last_move))
+}
@@ -0,0 +1,66 @@
+"""
+tests/test_rust_evaluate_array.py
+
+Тесты batch оценки через buffer protocol (rust_evaluate_array).
+Буферы array.array устроены так же, как numpy массивы: 'q' — это int64,
+тип numpy по умолчанию, 'Q' — uint64.
+"""
+
+import sys
+import os
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from array import array
+import pytest
+
+rust = pytest.importorskip("rust_peg_solver")
+
+
+def _boards():
+    start = rust.rust_standard_start("center")
+    boards = [start]
+    for move in rust.rust_solve(start)[:10]:
+        boards.append(rust.rust_apply_move(boards[-1], *move))
+    return boards
+
+
+def test_signed_move_counts_match_evaluate_position():
+    boards = _boards()
+    moves = [len(rust.rust_get_moves(pegs)) for pegs in boards]
+
+    scores = rust.rust_evaluate_array(array("Q", boards), array("q", moves))
+
+    expected = [rust.rust_evaluate_position(p, m) for p, m in zip(boards, moves)]
+    assert list(scores) == expected
+
+
+def test_signed_and_unsigned_move_counts_agree():
+    boards = _boards()
+    moves = list(range(len(boards)))
+
+    signed = rust.rust_evaluate_array(array("Q", boards), array("q", moves))
+    unsigned = rust.rust_evaluate_array(array("Q", boards), array("Q", moves))
+
+    assert list(signed) == list(unsigned)
+
+
+def test_negative_move_counts_are_rejected():
+    boards = _boards()
+    moves = [0] * len(boards)
+    moves[-1] = -1
+
+    with pytest.raises(ValueError):
+        rust.rust_evaluate_array(array("Q", boards), array("q", moves))
+
+
+def test_non_contiguous_buffer_is_read():
+    boards = _boards()
+    strided = memoryview(array("Q", boards + boards))[::2]
+
+    scores = rust.rust_evaluate_array(strided)
+
+    assert list(scores) == list(rust.rust_evaluate_array(array("Q", strided.tolist())))
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])